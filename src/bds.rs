@@ -0,0 +1,1152 @@
+//! Decode Comm-B Data Selector (BDS) registers carried in the MB field of Comm-B replies.
+//!
+//! The MB field does not identify which register it contains, so callers must either know which
+//! register was requested by the interrogator or use [`infer`] to find the registers the field is
+//! consistent with.
+
+use crate::parser::{decode_ac13_field, decode_callsign};
+use crate::types::{Altitude, AltitudeError, ICAOAddress, Velocity};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// Decoded contents of a Comm-B register.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BdsRegister {
+    /// Data link capability report (BDS 1,0)
+    DataLinkCapability(DataLinkCapability),
+    /// Common usage GICB capability report (BDS 1,7)
+    GICBCapability(GICBCapability),
+    /// Aircraft identification (BDS 2,0)
+    AircraftIdentification(AircraftIdentification),
+    /// ACAS active resolution advisory (BDS 3,0)
+    ResolutionAdvisory(ResolutionAdvisory),
+    /// Selected vertical intention (BDS 4,0)
+    SelectedVerticalIntention(SelectedVerticalIntention),
+    /// Meteorological routine air report (BDS 4,4)
+    MeteorologicalRoutineAirReport(MeteorologicalRoutineAirReport),
+    /// Meteorological hazard report (BDS 4,5)
+    MeteorologicalHazardReport(MeteorologicalHazardReport),
+    /// Track and turn report (BDS 5,0)
+    TrackAndTurnReport(TrackAndTurnReport),
+    /// Heading and speed report (BDS 6,0)
+    HeadingAndSpeedReport(HeadingAndSpeedReport),
+}
+
+/// Known state of an aircraft, such as the one reported in its latest airborne velocity message,
+/// used to rank candidate registers.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Reference {
+    /// Ground speed in knots
+    pub ground_speed: f64,
+    /// Track angle in degrees
+    pub track: f64,
+}
+
+impl TryFrom<&Velocity> for Reference {
+    type Error = UnavailableReference;
+
+    /// Uses the ground speed and track of an airborne velocity message as the reference.
+    fn try_from(velocity: &Velocity) -> Result<Self, Self::Error> {
+        match velocity {
+            Velocity::GroundSpeed {
+                heading: Some(track),
+                ground_speed: Some(ground_speed),
+                ..
+            } => Ok(Reference {
+                ground_speed: *ground_speed,
+                track: *track,
+            }),
+            _ => Err(UnavailableReference),
+        }
+    }
+}
+
+/// Error returned when a velocity cannot be used as a [`Reference`], as it does not report both the
+/// ground speed and the track of the aircraft.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct UnavailableReference;
+
+impl fmt::Display for UnavailableReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "velocity does not report both ground speed and track")
+    }
+}
+
+impl Error for UnavailableReference {}
+
+/// Data link capability report (BDS 1,0).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DataLinkCapability {
+    /// True if the report is continued in another register
+    pub continuation: bool,
+    /// True if the transponder supports the overlay command
+    pub overlay_command_capability: bool,
+    /// Mode S subnetwork version number, 0 if the subnetwork is not available
+    pub subnetwork_version: u8,
+    /// True if the transponder is a level 5 transponder supporting the enhanced protocol
+    pub enhanced_protocol: bool,
+    /// True if Mode S specific services are supported
+    pub specific_services_capability: bool,
+    /// Uplink extended length message (ELM) average throughput capability
+    pub uplink_elm_throughput: u8,
+    /// Downlink extended length message (ELM) throughput capability
+    pub downlink_elm_throughput: u8,
+    /// True if the aircraft identification capability is supported
+    pub aircraft_identification_capability: bool,
+    /// True if the squitter registers are being updated
+    pub squitter_capability: bool,
+    /// True if surveillance identifier (SI) codes are supported
+    pub surveillance_identifier_capability: bool,
+    /// True if the common usage GICB capability report (BDS 1,7) has changed
+    pub common_usage_gicb_capability: bool,
+    /// Status of the data terminal equipment (DTE) subaddresses 0 to 15
+    pub dte_status: u16,
+}
+
+/// Common usage GICB capability report (BDS 1,7).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GICBCapability {
+    /// Registers supported by the aircraft, identified by their BDS code (e.g. `0x40` for BDS 4,0)
+    pub supported_registers: Vec<u8>,
+}
+
+/// Aircraft identification (BDS 2,0).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AircraftIdentification {
+    /// Aircraft callsign
+    pub callsign: String,
+}
+
+/// ACAS resolution advisory (RA) report, as found in BDS 3,0 and ADS-B aircraft status messages
+/// (TC 28, subtype 2).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResolutionAdvisory {
+    /// Active resolution advisories (ARA)
+    pub active_resolution_advisories: ActiveResolutionAdvisories,
+    /// Resolution advisory complements (RAC) currently active
+    pub complements: ResolutionAdvisoryComplements,
+    /// True if the RA has been terminated
+    pub terminated: bool,
+    /// True if two or more threats are being processed simultaneously
+    pub multiple_threat_encounter: bool,
+    /// Identity of the threat, if available
+    pub threat_identity: Option<ThreatIdentity>,
+}
+
+/// Active resolution advisories (ARA) generated by ACAS.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ActiveResolutionAdvisories {
+    /// No vertical RA has been generated
+    NoVerticalAdvisory,
+    /// RA generated against one threat, or against multiple threats in the same sense
+    SingleSense {
+        /// True if the RA is corrective, false if it is preventive
+        corrective: bool,
+        /// True if the RA has a downward sense, false if it has an upward sense
+        downward_sense: bool,
+        /// True if the RA is an increased rate advisory
+        increased_rate: bool,
+        /// True if the RA is a sense reversal
+        sense_reversal: bool,
+        /// True if the RA is altitude crossing
+        altitude_crossing: bool,
+        /// True if the RA is positive (climb or descend), false if it limits the vertical speed
+        positive: bool,
+    },
+    /// RAs generated against multiple threats in different senses
+    MultipleSenses {
+        /// True if the RA requires a correction in the upward sense
+        upward_correction: bool,
+        /// True if the RA requires a positive climb
+        positive_climb: bool,
+        /// True if the RA requires a correction in the downward sense
+        downward_correction: bool,
+        /// True if the RA requires a positive descent
+        positive_descent: bool,
+        /// True if the RA requires a crossing
+        crossing: bool,
+        /// True if the RA is a sense reversal
+        sense_reversal: bool,
+    },
+}
+
+/// Resolution advisory complements (RAC) received from other ACAS equipped aircraft.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResolutionAdvisoryComplements {
+    /// Do not pass below
+    pub do_not_pass_below: bool,
+    /// Do not pass above
+    pub do_not_pass_above: bool,
+    /// Do not turn left
+    pub do_not_turn_left: bool,
+    /// Do not turn right
+    pub do_not_turn_right: bool,
+}
+
+/// Identity of the threat that caused an RA.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ThreatIdentity {
+    /// ICAO address of a Mode S equipped threat
+    Address(ICAOAddress),
+    /// Position of a threat without a Mode S transponder
+    Position {
+        /// Altitude of the threat, `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+        /// Range in tenths of a nautical mile, 0 if closer than 0.05 NM and 126 if farther than
+        /// 12.55 NM
+        range: Option<u8>,
+        /// Lower bound of the 6 degree sector containing the bearing of the threat, relative to
+        /// the heading of the aircraft
+        bearing: Option<u16>,
+    },
+}
+
+/// Source of the altitude an aircraft is targeting.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TargetAltitudeSource {
+    /// Unknown
+    Unknown,
+    /// Aircraft altitude
+    AircraftAltitude,
+    /// MCP/FCU selected altitude
+    MCPSelectedAltitude,
+    /// FMS selected altitude
+    FMSSelectedAltitude,
+}
+
+/// Selected vertical intention (BDS 4,0).
+#[derive(Debug, PartialEq, Clone)]
+pub struct SelectedVerticalIntention {
+    /// MCP/FCU selected altitude in feet
+    pub mcp_selected_altitude: Option<u16>,
+    /// FMS selected altitude in feet
+    pub fms_selected_altitude: Option<u16>,
+    /// Barometric pressure setting in millibars
+    pub barometric_pressure_setting: Option<f64>,
+    /// True if VNAV mode is engaged
+    pub vnav_mode: Option<bool>,
+    /// True if altitude hold mode is engaged
+    pub altitude_hold_mode: Option<bool>,
+    /// True if approach mode is engaged
+    pub approach_mode: Option<bool>,
+    /// Source of the target altitude
+    pub target_altitude_source: Option<TargetAltitudeSource>,
+}
+
+/// Source of the data in a meteorological routine air report.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MeteorologicalSource {
+    /// Invalid
+    Invalid,
+    /// Inertial navigation system
+    INS,
+    /// Global navigation satellite system
+    GNSS,
+    /// DME/DME
+    DMEDME,
+    /// VOR/DME
+    VORDME,
+    /// Reserved value
+    Reserved(u8),
+}
+
+/// Severity of a meteorological hazard.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HazardSeverity {
+    /// Nil
+    Nil,
+    /// Light
+    Light,
+    /// Moderate
+    Moderate,
+    /// Severe
+    Severe,
+}
+
+/// Meteorological routine air report (BDS 4,4).
+#[derive(Debug, PartialEq, Clone)]
+pub struct MeteorologicalRoutineAirReport {
+    /// Source of the reported data
+    pub source: MeteorologicalSource,
+    /// Wind speed in knots
+    pub wind_speed: Option<u16>,
+    /// Direction the wind is blowing from in degrees relative to true north
+    pub wind_direction: Option<f64>,
+    /// Static air temperature in degrees Celsius
+    pub static_air_temperature: f64,
+    /// Average static pressure in hectopascals
+    pub average_static_pressure: Option<u16>,
+    /// Turbulence
+    pub turbulence: Option<HazardSeverity>,
+    /// Relative humidity in percent
+    pub humidity: Option<f64>,
+}
+
+/// Meteorological hazard report (BDS 4,5).
+#[derive(Debug, PartialEq, Clone)]
+pub struct MeteorologicalHazardReport {
+    /// Turbulence
+    pub turbulence: Option<HazardSeverity>,
+    /// Wind shear
+    pub wind_shear: Option<HazardSeverity>,
+    /// Microburst
+    pub microburst: Option<HazardSeverity>,
+    /// Icing
+    pub icing: Option<HazardSeverity>,
+    /// Wake vortex
+    pub wake_vortex: Option<HazardSeverity>,
+    /// Static air temperature in degrees Celsius
+    pub static_air_temperature: Option<f64>,
+    /// Average static pressure in hectopascals
+    pub average_static_pressure: Option<u16>,
+    /// Radio height in feet
+    pub radio_height: Option<u16>,
+}
+
+/// Track and turn report (BDS 5,0).
+#[derive(Debug, PartialEq, Clone)]
+pub struct TrackAndTurnReport {
+    /// Roll angle in degrees, positive values indicate the right wing is down
+    pub roll_angle: Option<f64>,
+    /// True track angle in degrees
+    pub true_track: Option<f64>,
+    /// Ground speed in knots
+    pub ground_speed: Option<u16>,
+    /// Track angle rate in degrees per second, positive values indicate a right turn
+    pub track_angle_rate: Option<f64>,
+    /// True airspeed in knots
+    pub true_airspeed: Option<u16>,
+}
+
+/// Heading and speed report (BDS 6,0).
+#[derive(Debug, PartialEq, Clone)]
+pub struct HeadingAndSpeedReport {
+    /// Magnetic heading in degrees
+    pub magnetic_heading: Option<f64>,
+    /// Indicated airspeed in knots
+    pub indicated_airspeed: Option<u16>,
+    /// Mach number
+    pub mach: Option<f64>,
+    /// Barometric altitude rate in feet per minute
+    pub barometric_altitude_rate: Option<i16>,
+    /// Inertial vertical velocity in feet per minute
+    pub inertial_vertical_velocity: Option<i16>,
+}
+
+// Extracts `len` bits starting at bit `start` of the MB field. Bits are numbered from 1 to 56 as in
+// ICAO Doc 9871 to make it easier to cross-check register layouts.
+fn field(mb: &[u8; 7], start: u32, len: u32) -> u32 {
+    let value = mb.iter().fold(0u64, |acc, b| (acc << 8) | u64::from(*b));
+    ((value >> (56 - (start + len - 1))) & ((1 << len) - 1)) as u32
+}
+
+// Extracts a field guarded by a status bit at `start`, returning `None` if the status bit is not set.
+fn status_field(mb: &[u8; 7], start: u32, len: u32) -> Option<u32> {
+    if field(mb, start, 1) == 1 {
+        Some(field(mb, start + 1, len))
+    } else {
+        None
+    }
+}
+
+// Extracts a two's complement field guarded by a status bit at `start`, made of a sign bit followed
+// by `len` bits.
+fn signed_status_field(mb: &[u8; 7], start: u32, len: u32) -> Option<i32> {
+    if field(mb, start, 1) == 1 {
+        Some(signed_field(mb, start + 1, len))
+    } else {
+        None
+    }
+}
+
+// Extracts a two's complement field without a status bit, made of a sign bit at `start` followed by
+// `len` bits.
+fn signed_field(mb: &[u8; 7], start: u32, len: u32) -> i32 {
+    let v = field(mb, start, len + 1);
+    if v & (1 << len) != 0 {
+        v as i32 - (1 << (len + 1))
+    } else {
+        v as i32
+    }
+}
+
+fn to_hazard_severity(value: u32) -> HazardSeverity {
+    match value {
+        0 => HazardSeverity::Nil,
+        1 => HazardSeverity::Light,
+        2 => HazardSeverity::Moderate,
+        _ => HazardSeverity::Severe,
+    }
+}
+
+fn to_heading(angle: f64) -> f64 {
+    if angle < 0.0 {
+        angle + 360.0
+    } else {
+        angle
+    }
+}
+
+/// Decodes an MB field as a data link capability report register (BDS 1,0).
+pub fn decode_bds10(mb: &[u8; 7]) -> DataLinkCapability {
+    DataLinkCapability {
+        continuation: field(mb, 9, 1) == 1,
+        overlay_command_capability: field(mb, 15, 1) == 1,
+        subnetwork_version: field(mb, 17, 7) as u8,
+        enhanced_protocol: field(mb, 24, 1) == 1,
+        specific_services_capability: field(mb, 25, 1) == 1,
+        uplink_elm_throughput: field(mb, 26, 3) as u8,
+        downlink_elm_throughput: field(mb, 29, 4) as u8,
+        aircraft_identification_capability: field(mb, 33, 1) == 1,
+        squitter_capability: field(mb, 34, 1) == 1,
+        surveillance_identifier_capability: field(mb, 35, 1) == 1,
+        common_usage_gicb_capability: field(mb, 36, 1) == 1,
+        dte_status: field(mb, 41, 16) as u16,
+    }
+}
+
+// Registers whose support is reported by each of the first 24 bits of BDS 1,7.
+const GICB_REGISTERS: [u8; 24] = [
+    0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x20, 0x21, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x48, 0x50,
+    0x51, 0x52, 0x53, 0x54, 0x55, 0x56, 0x5F, 0x60,
+];
+
+/// Decodes an MB field as a common usage GICB capability report register (BDS 1,7).
+pub fn decode_bds17(mb: &[u8; 7]) -> GICBCapability {
+    GICBCapability {
+        supported_registers: GICB_REGISTERS
+            .iter()
+            .zip(1..)
+            .filter(|(_, bit)| field(mb, *bit, 1) == 1)
+            .map(|(register, _)| *register)
+            .collect(),
+    }
+}
+
+/// Decodes an MB field as an aircraft identification register (BDS 2,0).
+pub fn decode_bds20(mb: &[u8; 7]) -> AircraftIdentification {
+    AircraftIdentification {
+        callsign: decode_callsign((0..8).map(|i| field(mb, 9 + i * 6, 6) as u8).collect()),
+    }
+}
+
+// The threat identity data (TID) is either the ICAO address of the threat, or its altitude code
+// (TIDA), range (TIDR) and bearing (TIDB) depending on the threat type indicator (TTI).
+fn decode_threat_identity(mb: &[u8; 7]) -> Option<ThreatIdentity> {
+    match field(mb, 29, 2) {
+        1 => {
+            let address = field(mb, 31, 24);
+            Some(ThreatIdentity::Address(ICAOAddress(
+                (address >> 16) as u8,
+                (address >> 8) as u8,
+                address as u8,
+            )))
+        }
+        2 => {
+            let range = field(mb, 44, 7);
+            let bearing = field(mb, 51, 6);
+            Some(ThreatIdentity::Position {
+                altitude: decode_ac13_field(field(mb, 31, 13) as u16),
+                range: (range != 0).then(|| (range - 1) as u8),
+                bearing: (1..=60)
+                    .contains(&bearing)
+                    .then(|| ((bearing - 1) * 6) as u16),
+            })
+        }
+        _ => None,
+    }
+}
+
+// Decodes an RA report laid out as in BDS 3,0. Bits 1-8 are ignored, as they contain the BDS code
+// for Comm-B replies and the type code and subtype for ADS-B messages.
+pub(crate) fn decode_resolution_advisory(mb: &[u8; 7]) -> ResolutionAdvisory {
+    let multiple_threat_encounter = field(mb, 28, 1) == 1;
+    let bit = |n| field(mb, n, 1) == 1;
+    let active_resolution_advisories = if bit(9) {
+        ActiveResolutionAdvisories::SingleSense {
+            corrective: bit(10),
+            downward_sense: bit(11),
+            increased_rate: bit(12),
+            sense_reversal: bit(13),
+            altitude_crossing: bit(14),
+            positive: bit(15),
+        }
+    } else if multiple_threat_encounter {
+        ActiveResolutionAdvisories::MultipleSenses {
+            upward_correction: bit(10),
+            positive_climb: bit(11),
+            downward_correction: bit(12),
+            positive_descent: bit(13),
+            crossing: bit(14),
+            sense_reversal: bit(15),
+        }
+    } else {
+        ActiveResolutionAdvisories::NoVerticalAdvisory
+    };
+    ResolutionAdvisory {
+        active_resolution_advisories,
+        complements: ResolutionAdvisoryComplements {
+            do_not_pass_below: bit(23),
+            do_not_pass_above: bit(24),
+            do_not_turn_left: bit(25),
+            do_not_turn_right: bit(26),
+        },
+        terminated: bit(27),
+        multiple_threat_encounter,
+        threat_identity: decode_threat_identity(mb),
+    }
+}
+
+/// Decodes an MB field as an ACAS active resolution advisory register (BDS 3,0).
+pub fn decode_bds30(mb: &[u8; 7]) -> ResolutionAdvisory {
+    decode_resolution_advisory(mb)
+}
+
+/// Decodes an MB field as a selected vertical intention register (BDS 4,0).
+pub fn decode_bds40(mb: &[u8; 7]) -> SelectedVerticalIntention {
+    let modes = status_field(mb, 48, 3);
+    SelectedVerticalIntention {
+        mcp_selected_altitude: status_field(mb, 1, 12).map(|v| (v * 16) as u16),
+        fms_selected_altitude: status_field(mb, 14, 12).map(|v| (v * 16) as u16),
+        barometric_pressure_setting: status_field(mb, 27, 12).map(|v| f64::from(v) * 0.1 + 800.0),
+        vnav_mode: modes.map(|v| v & 0b100 != 0),
+        altitude_hold_mode: modes.map(|v| v & 0b010 != 0),
+        approach_mode: modes.map(|v| v & 0b001 != 0),
+        target_altitude_source: status_field(mb, 54, 2).map(|v| match v {
+            1 => TargetAltitudeSource::AircraftAltitude,
+            2 => TargetAltitudeSource::MCPSelectedAltitude,
+            3 => TargetAltitudeSource::FMSSelectedAltitude,
+            _ => TargetAltitudeSource::Unknown,
+        }),
+    }
+}
+
+/// Decodes an MB field as a meteorological routine air report register (BDS 4,4). The static air
+/// temperature has a resolution of 0.25 degrees Celsius and, unlike the other fields, is not guarded
+/// by a status bit.
+pub fn decode_bds44(mb: &[u8; 7]) -> MeteorologicalRoutineAirReport {
+    let wind = status_field(mb, 5, 18);
+    MeteorologicalRoutineAirReport {
+        source: match field(mb, 1, 4) {
+            0 => MeteorologicalSource::Invalid,
+            1 => MeteorologicalSource::INS,
+            2 => MeteorologicalSource::GNSS,
+            3 => MeteorologicalSource::DMEDME,
+            4 => MeteorologicalSource::VORDME,
+            v => MeteorologicalSource::Reserved(v as u8),
+        },
+        wind_speed: wind.map(|v| (v >> 9) as u16),
+        wind_direction: wind.map(|v| f64::from(v & 0x1FF) * 180.0 / 256.0),
+        static_air_temperature: f64::from(signed_field(mb, 24, 10)) * 0.25,
+        average_static_pressure: status_field(mb, 35, 11).map(|v| v as u16),
+        turbulence: status_field(mb, 47, 2).map(to_hazard_severity),
+        humidity: status_field(mb, 50, 6).map(|v| f64::from(v) * 100.0 / 64.0),
+    }
+}
+
+/// Decodes an MB field as a meteorological hazard report register (BDS 4,5).
+pub fn decode_bds45(mb: &[u8; 7]) -> MeteorologicalHazardReport {
+    MeteorologicalHazardReport {
+        turbulence: status_field(mb, 1, 2).map(to_hazard_severity),
+        wind_shear: status_field(mb, 4, 2).map(to_hazard_severity),
+        microburst: status_field(mb, 7, 2).map(to_hazard_severity),
+        icing: status_field(mb, 10, 2).map(to_hazard_severity),
+        wake_vortex: status_field(mb, 13, 2).map(to_hazard_severity),
+        static_air_temperature: signed_status_field(mb, 16, 9).map(|v| f64::from(v) * 0.25),
+        average_static_pressure: status_field(mb, 27, 11).map(|v| v as u16),
+        radio_height: status_field(mb, 39, 12).map(|v| (v * 16) as u16),
+    }
+}
+
+/// Decodes an MB field as a track and turn report register (BDS 5,0).
+pub fn decode_bds50(mb: &[u8; 7]) -> TrackAndTurnReport {
+    TrackAndTurnReport {
+        roll_angle: signed_status_field(mb, 1, 9).map(|v| f64::from(v) * 45.0 / 256.0),
+        true_track: signed_status_field(mb, 12, 10)
+            .map(|v| to_heading(f64::from(v) * 90.0 / 512.0)),
+        ground_speed: status_field(mb, 24, 10).map(|v| (v * 2) as u16),
+        track_angle_rate: signed_status_field(mb, 35, 9).map(|v| f64::from(v) * 8.0 / 256.0),
+        true_airspeed: status_field(mb, 46, 10).map(|v| (v * 2) as u16),
+    }
+}
+
+/// Decodes an MB field as a heading and speed report register (BDS 6,0).
+pub fn decode_bds60(mb: &[u8; 7]) -> HeadingAndSpeedReport {
+    HeadingAndSpeedReport {
+        magnetic_heading: signed_status_field(mb, 1, 10)
+            .map(|v| to_heading(f64::from(v) * 90.0 / 512.0)),
+        indicated_airspeed: status_field(mb, 13, 10).map(|v| v as u16),
+        mach: status_field(mb, 24, 10).map(|v| f64::from(v) * 2.048 / 512.0),
+        barometric_altitude_rate: signed_status_field(mb, 35, 9).map(|v| (v * 32) as i16),
+        inertial_vertical_velocity: signed_status_field(mb, 46, 9).map(|v| (v * 32) as i16),
+    }
+}
+
+// Checks that a field is zero when its status bit is not set.
+fn status_consistent(mb: &[u8; 7], start: u32, len: u32) -> bool {
+    field(mb, start, 1) == 1 || field(mb, start + 1, len) == 0
+}
+
+fn angle_difference(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+fn is_bds10(mb: &[u8; 7]) -> Option<BdsRegister> {
+    if field(mb, 1, 8) != 0x10 || field(mb, 10, 5) != 0 {
+        return None;
+    }
+    let r = decode_bds10(mb);
+    // The overlay command capability was introduced with subnetwork version 5
+    if r.overlay_command_capability != (r.subnetwork_version >= 5) {
+        return None;
+    }
+    Some(BdsRegister::DataLinkCapability(r))
+}
+
+fn is_bds17(mb: &[u8; 7]) -> Option<BdsRegister> {
+    // BDS 2,0 must be supported by any aircraft reporting its GICB capability
+    if field(mb, 7, 1) != 1 || field(mb, 25, 32) != 0 {
+        return None;
+    }
+    Some(BdsRegister::GICBCapability(decode_bds17(mb)))
+}
+
+fn is_bds20(mb: &[u8; 7]) -> Option<BdsRegister> {
+    if field(mb, 1, 8) != 0x20 {
+        return None;
+    }
+    let r = decode_bds20(mb);
+    if r.callsign.contains('#') {
+        return None;
+    }
+    Some(BdsRegister::AircraftIdentification(r))
+}
+
+fn is_bds30(mb: &[u8; 7]) -> Option<BdsRegister> {
+    // Bits 16-22 of the ARA are reserved for ACAS III
+    if field(mb, 1, 8) != 0x30 || field(mb, 16, 7) != 0 {
+        return None;
+    }
+    match field(mb, 29, 2) {
+        1 if field(mb, 55, 2) != 0 => return None,
+        3 => return None,
+        _ => (),
+    }
+    Some(BdsRegister::ResolutionAdvisory(decode_bds30(mb)))
+}
+
+fn is_bds40(mb: &[u8; 7]) -> Option<BdsRegister> {
+    let consistent = [(1, 12), (14, 12), (27, 12), (48, 3), (54, 2)]
+        .iter()
+        .all(|(start, len)| status_consistent(mb, *start, *len));
+    if !consistent || field(mb, 40, 8) != 0 || field(mb, 52, 2) != 0 {
+        return None;
+    }
+    let r = decode_bds40(mb);
+    let altitude_valid = |a: Option<u16>| a.map_or(true, |a| a <= 50_000);
+    if !altitude_valid(r.mcp_selected_altitude)
+        || !altitude_valid(r.fms_selected_altitude)
+        || r.barometric_pressure_setting
+            .is_some_and(|p| !(850.0..=1100.0).contains(&p))
+    {
+        return None;
+    }
+    Some(BdsRegister::SelectedVerticalIntention(r))
+}
+
+fn is_bds44(mb: &[u8; 7]) -> Option<BdsRegister> {
+    let consistent = [(5, 18), (35, 11), (47, 2), (50, 6)]
+        .iter()
+        .all(|(start, len)| status_consistent(mb, *start, *len));
+    if !consistent {
+        return None;
+    }
+    let r = decode_bds44(mb);
+    if matches!(
+        r.source,
+        MeteorologicalSource::Invalid | MeteorologicalSource::Reserved(_)
+    ) || r.wind_speed.is_some_and(|v| v > 250)
+        || !(-80.0..=60.0).contains(&r.static_air_temperature)
+    {
+        return None;
+    }
+    Some(BdsRegister::MeteorologicalRoutineAirReport(r))
+}
+
+fn is_bds50(mb: &[u8; 7]) -> Option<BdsRegister> {
+    let consistent = [(1, 10), (12, 11), (24, 10), (35, 10), (46, 10)]
+        .iter()
+        .all(|(start, len)| status_consistent(mb, *start, *len));
+    if !consistent {
+        return None;
+    }
+    let r = decode_bds50(mb);
+    if r.roll_angle.is_some_and(|a| a.abs() > 50.0)
+        || r.ground_speed.is_some_and(|v| v > 600)
+        || r.true_airspeed.is_some_and(|v| v > 500)
+    {
+        return None;
+    }
+    if let (Some(gs), Some(tas)) = (r.ground_speed, r.true_airspeed) {
+        if gs.abs_diff(tas) > 200 {
+            return None;
+        }
+    }
+    Some(BdsRegister::TrackAndTurnReport(r))
+}
+
+fn is_bds60(mb: &[u8; 7]) -> Option<BdsRegister> {
+    let consistent = [(1, 11), (13, 10), (24, 10), (35, 10), (46, 10)]
+        .iter()
+        .all(|(start, len)| status_consistent(mb, *start, *len));
+    if !consistent {
+        return None;
+    }
+    let r = decode_bds60(mb);
+    let rate_valid = |v: Option<i16>| v.map_or(true, |v| v.abs() <= 6000);
+    if r.indicated_airspeed.is_some_and(|v| v > 500)
+        || r.mach.is_some_and(|m| m > 1.0)
+        || !rate_valid(r.barometric_altitude_rate)
+        || !rate_valid(r.inertial_vertical_velocity)
+    {
+        return None;
+    }
+    Some(BdsRegister::HeadingAndSpeedReport(r))
+}
+
+// Registers which contain their own BDS code in the first 8 bits of the MB field.
+fn self_identifying(register: &BdsRegister) -> bool {
+    matches!(
+        register,
+        BdsRegister::DataLinkCapability(_)
+            | BdsRegister::AircraftIdentification(_)
+            | BdsRegister::ResolutionAdvisory(_)
+    )
+}
+
+// Number of fields with their status bit set, used to prefer candidates with more information.
+fn available_fields(register: &BdsRegister) -> usize {
+    match register {
+        BdsRegister::DataLinkCapability(_)
+        | BdsRegister::GICBCapability(_)
+        | BdsRegister::AircraftIdentification(_)
+        | BdsRegister::ResolutionAdvisory(_) => 1,
+        BdsRegister::SelectedVerticalIntention(r) => [
+            r.mcp_selected_altitude.is_some(),
+            r.fms_selected_altitude.is_some(),
+            r.barometric_pressure_setting.is_some(),
+            r.vnav_mode.is_some(),
+            r.target_altitude_source.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count(),
+        BdsRegister::MeteorologicalRoutineAirReport(r) => [
+            r.wind_speed.is_some(),
+            true,
+            r.average_static_pressure.is_some(),
+            r.turbulence.is_some(),
+            r.humidity.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count(),
+        BdsRegister::MeteorologicalHazardReport(r) => [
+            r.turbulence.is_some(),
+            r.wind_shear.is_some(),
+            r.microburst.is_some(),
+            r.icing.is_some(),
+            r.wake_vortex.is_some(),
+            r.static_air_temperature.is_some(),
+            r.average_static_pressure.is_some(),
+            r.radio_height.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count(),
+        BdsRegister::TrackAndTurnReport(r) => [
+            r.roll_angle.is_some(),
+            r.true_track.is_some(),
+            r.ground_speed.is_some(),
+            r.track_angle_rate.is_some(),
+            r.true_airspeed.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count(),
+        BdsRegister::HeadingAndSpeedReport(r) => [
+            r.magnetic_heading.is_some(),
+            r.indicated_airspeed.is_some(),
+            r.mach.is_some(),
+            r.barometric_altitude_rate.is_some(),
+            r.inertial_vertical_velocity.is_some(),
+        ]
+        .iter()
+        .filter(|v| **v)
+        .count(),
+    }
+}
+
+// Deviation from the reference velocity, normalised between 0 and 1 so that both registers are
+// ranked on the same scale. Each register is compared using the mean of its track or heading error
+// relative to half a turn, and of its ground speed or indicated airspeed error relative to the
+// reference ground speed.
+fn deviation(register: &BdsRegister, reference: &Reference) -> f64 {
+    let (track, speed) = match register {
+        BdsRegister::TrackAndTurnReport(r) => (r.true_track, r.ground_speed),
+        BdsRegister::HeadingAndSpeedReport(r) => (r.magnetic_heading, r.indicated_airspeed),
+        _ => return 0.0,
+    };
+    let errors: Vec<f64> = [
+        track.map(|t| angle_difference(t, reference.track) / 180.0),
+        speed.map(|v| {
+            ((f64::from(v) - reference.ground_speed).abs() / reference.ground_speed.max(1.0))
+                .min(1.0)
+        }),
+    ]
+    .iter()
+    .flatten()
+    .copied()
+    .collect();
+    if errors.is_empty() {
+        0.0
+    } else {
+        errors.iter().sum::<f64>() / errors.len() as f64
+    }
+}
+
+fn is_velocity_report(register: &BdsRegister) -> bool {
+    matches!(
+        register,
+        BdsRegister::TrackAndTurnReport(_) | BdsRegister::HeadingAndSpeedReport(_)
+    )
+}
+
+/// Infers which registers an MB field may contain by checking it against every supported register
+/// for status bit consistency, reserved bits and physically plausible values. Returns the matching
+/// registers ranked from most to least likely: registers containing their own BDS code (1,0, 2,0
+/// and 3,0) are ranked first, followed by registers reporting more fields.
+/// When a reference is provided, BDS 5,0 and 6,0 candidates are instead ranked between themselves by
+/// how closely their track or heading and speed match the reference, which helps telling them apart.
+///
+/// BDS 4,5 is never inferred as most of its fields can be mistaken for other registers, so it
+/// should only be decoded when it is known to have been requested.
+pub fn infer(mb: &[u8; 7], reference: Option<&Reference>) -> Vec<BdsRegister> {
+    if mb.iter().all(|b| *b == 0) {
+        return vec![];
+    }
+    let mut candidates: Vec<BdsRegister> = [
+        is_bds10, is_bds17, is_bds20, is_bds30, is_bds40, is_bds44, is_bds50, is_bds60,
+    ]
+    .iter()
+    .filter_map(|is_bds| is_bds(mb))
+    .collect();
+    candidates.sort_by(|a, b| {
+        self_identifying(b)
+            .cmp(&self_identifying(a))
+            .then(available_fields(b).cmp(&available_fields(a)))
+    });
+
+    // Reorder the BDS 5,0 and 6,0 candidates within the positions they occupy
+    if let Some(reference) = reference {
+        let positions: Vec<usize> = (0..candidates.len())
+            .filter(|i| is_velocity_report(&candidates[*i]))
+            .collect();
+        let mut velocity_reports: Vec<BdsRegister> =
+            positions.iter().map(|i| candidates[*i].clone()).collect();
+        velocity_reports.sort_by(|a, b| {
+            deviation(a, reference)
+                .total_cmp(&deviation(b, reference))
+                .then(available_fields(b).cmp(&available_fields(a)))
+        });
+        for (i, register) in positions.into_iter().zip(velocity_reports) {
+            candidates[i] = register;
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AirspeedType, AltitudeSource};
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn decode_data_link_capability() {
+        let mb = b"\x10\x80\x01\xBA\xA0\x00\x00";
+        let r = decode_bds10(mb);
+        assert!(r.continuation);
+        assert!(!r.overlay_command_capability);
+        assert_eq!(r.subnetwork_version, 0);
+        assert!(r.enhanced_protocol);
+        assert!(r.specific_services_capability);
+        assert_eq!(r.uplink_elm_throughput, 3);
+        assert_eq!(r.downlink_elm_throughput, 10);
+        assert!(r.aircraft_identification_capability);
+        assert!(!r.squitter_capability);
+        assert!(r.surveillance_identifier_capability);
+        assert!(!r.common_usage_gicb_capability);
+        assert_eq!(r.dte_status, 0);
+    }
+
+    #[test]
+    fn decode_gicb_capability() {
+        let mb = b"\xFA\x81\xC1\x00\x00\x00\x00";
+        assert_eq!(
+            decode_bds17(mb).supported_registers,
+            vec![0x05, 0x06, 0x07, 0x08, 0x09, 0x20, 0x40, 0x50, 0x51, 0x52, 0x60]
+        );
+    }
+
+    #[test]
+    fn decode_aircraft_identification() {
+        let mb = b"\x20\x2C\xC3\x71\xC3\x1D\xE0";
+        assert_eq!(decode_bds20(mb).callsign, "KLM1017 ");
+    }
+
+    #[test]
+    fn decode_resolution_advisory_with_address() {
+        let mb = b"\x30\xA0\x00\x05\x21\x49\x58";
+        assert_eq!(
+            decode_bds30(mb),
+            ResolutionAdvisory {
+                active_resolution_advisories: ActiveResolutionAdvisories::SingleSense {
+                    corrective: false,
+                    downward_sense: true,
+                    increased_rate: false,
+                    sense_reversal: false,
+                    altitude_crossing: false,
+                    positive: false,
+                },
+                complements: ResolutionAdvisoryComplements {
+                    do_not_pass_below: false,
+                    do_not_pass_above: false,
+                    do_not_turn_left: false,
+                    do_not_turn_right: false,
+                },
+                terminated: false,
+                multiple_threat_encounter: false,
+                threat_identity: Some(ThreatIdentity::Address(ICAOAddress(0x48, 0x52, 0x56))),
+            }
+        );
+    }
+
+    #[test]
+    fn decode_resolution_advisory_with_position() {
+        let mb = b"\x30\x00\x00\x38\x80\x05\x4C";
+        assert_eq!(
+            decode_bds30(mb),
+            ResolutionAdvisory {
+                active_resolution_advisories: ActiveResolutionAdvisories::MultipleSenses {
+                    upward_correction: false,
+                    positive_climb: false,
+                    downward_correction: false,
+                    positive_descent: false,
+                    crossing: false,
+                    sense_reversal: false,
+                },
+                complements: ResolutionAdvisoryComplements {
+                    do_not_pass_below: false,
+                    do_not_pass_above: false,
+                    do_not_turn_left: false,
+                    do_not_turn_right: false,
+                },
+                terminated: true,
+                multiple_threat_encounter: true,
+                threat_identity: Some(ThreatIdentity::Position {
+                    altitude: Ok(Some(Altitude {
+                        value: -1000,
+                        source: AltitudeSource::Barometric100Ft,
+                    })),
+                    range: Some(20),
+                    bearing: Some(66),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn decode_selected_vertical_intention() {
+        let mb = b"\x85\xE4\x2F\x31\x30\x00\x00";
+        let r = decode_bds40(mb);
+        assert_eq!(r.mcp_selected_altitude, Some(3008));
+        assert_eq!(r.fms_selected_altitude, Some(3008));
+        assert_approx_eq!(r.barometric_pressure_setting.unwrap(), 1020.0);
+        assert_eq!(r.vnav_mode, None);
+        assert_eq!(r.target_altitude_source, None);
+    }
+
+    #[test]
+    fn decode_meteorological_routine_air_report() {
+        let mb = b"\x18\x5B\xD5\xCF\x40\x00\x00";
+        let r = decode_bds44(mb);
+        assert_eq!(r.source, MeteorologicalSource::INS);
+        assert_eq!(r.wind_speed, Some(22));
+        assert_approx_eq!(r.wind_direction.unwrap(), 344.53125);
+        assert_approx_eq!(r.static_air_temperature, -48.75);
+        assert_eq!(r.average_static_pressure, None);
+        assert_eq!(r.turbulence, None);
+        assert_eq!(r.humidity, None);
+    }
+
+    #[test]
+    fn decode_meteorological_hazard_report() {
+        let mb = b"\xC2\x01\xDB\x00\x02\x08\x00";
+        let r = decode_bds45(mb);
+        assert_eq!(r.turbulence, Some(HazardSeverity::Moderate));
+        assert_eq!(r.wind_shear, None);
+        assert_eq!(r.microburst, Some(HazardSeverity::Nil));
+        assert_eq!(r.icing, None);
+        assert_eq!(r.wake_vortex, None);
+        assert_approx_eq!(r.static_air_temperature.unwrap(), -37.0);
+        assert_eq!(r.average_static_pressure, None);
+        assert_eq!(r.radio_height, Some(1024));
+    }
+
+    #[test]
+    fn decode_track_and_turn_report() {
+        let mb = b"\x81\x95\x15\x36\xE0\x24\xD4";
+        let r = decode_bds50(mb);
+        assert_approx_eq!(r.roll_angle.unwrap(), 2.109375);
+        assert_approx_eq!(r.true_track.unwrap(), 114.2578125);
+        assert_eq!(r.ground_speed, Some(438));
+        assert_approx_eq!(r.track_angle_rate.unwrap(), 0.125);
+        assert_eq!(r.true_airspeed, Some(424));
+    }
+
+    #[test]
+    fn decode_heading_and_speed_report() {
+        let mb = b"\x8F\x39\xF9\x1A\x7E\x27\xC4";
+        let r = decode_bds60(mb);
+        assert_approx_eq!(r.magnetic_heading.unwrap(), 42.71484375);
+        assert_eq!(r.indicated_airspeed, Some(252));
+        assert_approx_eq!(r.mach.unwrap(), 0.42);
+        assert_eq!(r.barometric_altitude_rate, Some(-1920));
+        assert_eq!(r.inertial_vertical_velocity, Some(-1920));
+    }
+
+    #[test]
+    fn infer_registers() {
+        let bds40 = b"\x85\xE4\x2F\x31\x30\x00\x00";
+        assert!(matches!(
+            infer(bds40, None).as_slice(),
+            [BdsRegister::SelectedVerticalIntention(_)]
+        ));
+        let bds50 = b"\x81\x95\x15\x36\xE0\x24\xD4";
+        let bds44 = b"\x18\x5B\xD5\xCF\x40\x00\x00";
+        assert!(matches!(
+            infer(bds44, None).as_slice(),
+            [BdsRegister::MeteorologicalRoutineAirReport(_)]
+        ));
+        assert!(matches!(
+            infer(bds50, None).as_slice(),
+            [BdsRegister::TrackAndTurnReport(_)]
+        ));
+        let bds60 = b"\x8F\x39\xF9\x1A\x7E\x27\xC4";
+        assert!(matches!(
+            infer(bds60, None).as_slice(),
+            [BdsRegister::HeadingAndSpeedReport(_)]
+        ));
+        let bds20 = b"\x20\x2C\xC3\x71\xC3\x1D\xE0";
+        assert!(matches!(
+            infer(bds20, None).first(),
+            Some(BdsRegister::AircraftIdentification(_))
+        ));
+        assert!(infer(&[0; 7], None).is_empty());
+    }
+
+    #[test]
+    fn infer_registers_with_reference() {
+        // Plausible both as BDS 5,0 (track 214, 32 kt) and BDS 6,0 (heading 34, 195 kt IAS)
+        let mb = b"\x8C\x39\x87\x04\x22\x1C\x5B";
+        let as_bds50 = Reference {
+            ground_speed: 32.0,
+            track: 214.0,
+        };
+        assert!(matches!(
+            infer(mb, Some(&as_bds50)).as_slice(),
+            [
+                BdsRegister::TrackAndTurnReport(_),
+                BdsRegister::HeadingAndSpeedReport(_)
+            ]
+        ));
+        let as_bds60 = Reference {
+            ground_speed: 200.0,
+            track: 34.0,
+        };
+        assert!(matches!(
+            infer(mb, Some(&as_bds60)).as_slice(),
+            [
+                BdsRegister::HeadingAndSpeedReport(_),
+                BdsRegister::TrackAndTurnReport(_)
+            ]
+        ));
+
+        // The track matches BDS 5,0 while its ground speed is far off and the heading of BDS 6,0
+        // is opposite: neither error should dominate because of its unit
+        let track_only = Reference {
+            ground_speed: 250.0,
+            track: 214.0,
+        };
+        assert!(matches!(
+            infer(mb, Some(&track_only)).as_slice(),
+            [
+                BdsRegister::TrackAndTurnReport(_),
+                BdsRegister::HeadingAndSpeedReport(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn infer_registers_with_reference_over_field_count() {
+        // BDS 5,0 (track 0, 32 kt) reports all of its fields while BDS 6,0 (heading 34) has no
+        // indicated airspeed, yet the reference matches BDS 6,0 better
+        let mb = b"\x8C\x30\x01\x04\x22\x1C\x5B";
+        assert!(matches!(
+            infer(mb, None).as_slice(),
+            [
+                BdsRegister::TrackAndTurnReport(_),
+                BdsRegister::HeadingAndSpeedReport(_)
+            ]
+        ));
+        let reference = Reference {
+            ground_speed: 200.0,
+            track: 34.0,
+        };
+        assert!(matches!(
+            infer(mb, Some(&reference)).as_slice(),
+            [
+                BdsRegister::HeadingAndSpeedReport(_),
+                BdsRegister::TrackAndTurnReport(_)
+            ]
+        ));
+    }
+
+    #[test]
+    fn reference_from_velocity() {
+        let velocity = Velocity::GroundSpeed {
+            east_west: Some(100),
+            north_south: Some(0),
+            heading: Some(90.0),
+            ground_speed: Some(100.0),
+            saturated: false,
+        };
+        assert_eq!(
+            Reference::try_from(&velocity),
+            Ok(Reference {
+                ground_speed: 100.0,
+                track: 90.0,
+            })
+        );
+        let velocity = Velocity::Airspeed {
+            heading: Some(90.0),
+            airspeed: Some(100),
+            airspeed_type: AirspeedType::True,
+            saturated: false,
+        };
+        assert_eq!(Reference::try_from(&velocity), Err(UnavailableReference));
+    }
+}
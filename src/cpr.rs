@@ -1,12 +1,104 @@
 //! Decode aircraft positions encoded in Compact Position Reporting (CPR) format.
 
-use crate::types::{CPRFrame, Parity, Position};
+mod decoder;
+mod filter;
+pub mod fixed;
+
+pub use decoder::*;
+pub use filter::*;
+
+use crate::types::{CPREncoding, CPRFrame, Parity, Position};
 use std::cmp;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime};
 
 const NZ: f64 = 15.0;
 const D_LAT_EVEN: f64 = 360.0 / (4.0 * NZ);
 const D_LAT_ODD: f64 = 360.0 / (4.0 * NZ - 1.0);
-const CPR_MAX: f64 = 131_072.0;
+const D_LAT_EVEN_SURFACE: f64 = 90.0 / (4.0 * NZ);
+const D_LAT_ODD_SURFACE: f64 = 90.0 / (4.0 * NZ - 1.0);
+
+/// Maximum time allowed between a pair of airborne frames for them to be decoded together.
+pub const AIRBORNE_MAX_INTERVAL: Duration = Duration::from_secs(10);
+/// Maximum time allowed between a pair of surface frames for them to be decoded together.
+pub const SURFACE_MAX_INTERVAL: Duration = Duration::from_secs(25);
+
+/// Error type used to convey why a pair of frames could not be decoded into a position.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CPRError {
+    /// Both frames have the same parity
+    SameParity,
+    /// The frames were received too far apart to be decoded together
+    FramesTooFarApart,
+    /// The even and odd latitudes fall in zones with a different number of longitude zones, which
+    /// happens when an aircraft crosses a zone boundary between frames
+    ZoneMismatch,
+    /// The decoded latitude is out of range
+    InvalidLatitude,
+    /// The frames are encoded differently than expected, such as surface frames being passed to
+    /// [`get_position`]
+    UnexpectedEncoding,
+}
+
+impl fmt::Display for CPRError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CPRError::SameParity => "frames must have opposite parity",
+                CPRError::FramesTooFarApart => "frames were received too far apart",
+                CPRError::ZoneMismatch => "frames are in different longitude zones",
+                CPRError::InvalidLatitude => "decoded latitude is out of range",
+                CPRError::UnexpectedEncoding => "frames have an unexpected encoding",
+            }
+        )
+    }
+}
+
+impl Error for CPRError {}
+
+/// Times at which a pair of frames were received, used to reject frames received too far apart to
+/// be decoded together.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReceiveTimes {
+    /// Time the oldest frame was received
+    pub first: SystemTime,
+    /// Time the latest frame was received
+    pub latest: SystemTime,
+    /// Maximum time allowed between both frames, overriding [`AIRBORNE_MAX_INTERVAL`] or
+    /// [`SURFACE_MAX_INTERVAL`] depending on the encoding of the frames
+    pub max_interval: Option<Duration>,
+}
+
+impl ReceiveTimes {
+    /// Receive times of a pair of frames, allowing up to [`AIRBORNE_MAX_INTERVAL`] between airborne
+    /// or coarse TIS-B frames and up to [`SURFACE_MAX_INTERVAL`] between surface frames.
+    pub fn new(first: SystemTime, latest: SystemTime) -> Self {
+        ReceiveTimes {
+            first,
+            latest,
+            max_interval: None,
+        }
+    }
+
+    fn check(&self, surface: bool) -> Result<(), CPRError> {
+        let interval = match self.latest.duration_since(self.first) {
+            Ok(interval) => interval,
+            Err(e) => e.duration(),
+        };
+        let max_interval = self.max_interval.unwrap_or(if surface {
+            SURFACE_MAX_INTERVAL
+        } else {
+            AIRBORNE_MAX_INTERVAL
+        });
+        if interval > max_interval {
+            return Err(CPRError::FramesTooFarApart);
+        }
+        Ok(())
+    }
+}
 
 // The NL function uses the precomputed table from 1090-WP-9-14
 // This code is translated from https://github.com/wiedehopf/readsb/blob/dev/cpr.c
@@ -199,37 +291,21 @@ pub(crate) fn cpr_nl(lat: f64) -> u64 {
 /// Calculates a globally unambiguous position based on a pair of frames containing position information
 /// encoded in CPR format. A position is returned when passed a tuple containing two frames of opposite parity
 /// (even and odd). The frames in the tuple should be ordered according to when they were received: the first
-/// frame being the oldest frame and the second frame being the latest.
-pub fn get_position(cpr_frames: (&CPRFrame, &CPRFrame)) -> Option<Position> {
+/// frame being the oldest frame and the second frame being the latest. If the times at which the frames were
+/// received are provided, frames received too far apart are rejected.
+pub fn get_position(
+    cpr_frames: (&CPRFrame, &CPRFrame),
+    receive_times: Option<&ReceiveTimes>,
+) -> Result<Position, CPRError> {
     let latest_frame = cpr_frames.1;
-    let (even_frame, odd_frame) = match cpr_frames {
-        (
-            even @ CPRFrame {
-                parity: Parity::Even,
-                ..
-            },
-            odd @ CPRFrame {
-                parity: Parity::Odd,
-                ..
-            },
-        )
-        | (
-            odd @ CPRFrame {
-                parity: Parity::Odd,
-                ..
-            },
-            even @ CPRFrame {
-                parity: Parity::Even,
-                ..
-            },
-        ) => (even, odd),
-        _ => return None,
-    };
+    let (even_frame, odd_frame) = split_parity(cpr_frames)?;
+    check_encodings(cpr_frames, false)?;
+    if let Some(receive_times) = receive_times {
+        receive_times.check(false)?;
+    }
 
-    let cpr_lat_even = even_frame.position.latitude / CPR_MAX;
-    let cpr_lon_even = even_frame.position.longitude / CPR_MAX;
-    let cpr_lat_odd = odd_frame.position.latitude / CPR_MAX;
-    let cpr_lon_odd = odd_frame.position.longitude / CPR_MAX;
+    let (cpr_lat_even, cpr_lon_even) = cpr_coordinates(even_frame);
+    let (cpr_lat_odd, cpr_lon_odd) = cpr_coordinates(odd_frame);
 
     let j = (59.0 * cpr_lat_even - 60.0 * cpr_lat_odd + 0.5).floor();
 
@@ -244,6 +320,8 @@ pub fn get_position(cpr_frames: (&CPRFrame, &CPRFrame)) -> Option<Position> {
         lat_odd -= 360.0;
     }
 
+    check_latitudes(lat_even, lat_odd)?;
+
     let lat = if latest_frame == even_frame {
         lat_even
     } else {
@@ -252,12 +330,218 @@ pub fn get_position(cpr_frames: (&CPRFrame, &CPRFrame)) -> Option<Position> {
 
     let (lat, lon) = get_lat_lon(lat, cpr_lon_even, cpr_lon_odd, &latest_frame.parity);
 
+    Ok(Position {
+        latitude: lat,
+        longitude: lon,
+    })
+}
+
+/// Calculates the position of an airborne aircraft based on a single frame containing position
+/// information encoded in CPR format. The reference position, such as the location of the receiver or
+/// the last known position of the aircraft, must be within 180 NM of the aircraft for the decoded
+/// position to be correct. Unlike [`get_position`], this allows a position to be calculated as soon
+/// as the first frame from an aircraft is received. Returns `None` for surface frames, and when the
+/// decoded position is more than half a zone away from the reference.
+pub fn get_local_position(cpr_frame: &CPRFrame, reference: &Position) -> Option<Position> {
+    if is_surface(cpr_frame) {
+        return None;
+    }
+    get_local_position_in_zone(cpr_frame, reference, 360.0)
+}
+
+/// Calculates the position of an aircraft on the surface based on a pair of frames containing position
+/// information encoded in CPR format, with the frames ordered as in [`get_position`]. Surface positions
+/// are encoded using zones four times smaller than airborne positions, so each pair of frames matches
+/// four possible positions. The one closest to the reference position, such as the location of the
+/// receiver or airport, is returned.
+pub fn get_surface_position(
+    cpr_frames: (&CPRFrame, &CPRFrame),
+    reference: &Position,
+    receive_times: Option<&ReceiveTimes>,
+) -> Result<Position, CPRError> {
+    let latest_frame = cpr_frames.1;
+    let (even_frame, odd_frame) = split_parity(cpr_frames)?;
+    check_encodings(cpr_frames, true)?;
+    if let Some(receive_times) = receive_times {
+        receive_times.check(true)?;
+    }
+
+    let (cpr_lat_even, cpr_lon_even) = cpr_coordinates(even_frame);
+    let (cpr_lat_odd, cpr_lon_odd) = cpr_coordinates(odd_frame);
+
+    let j = (59.0 * cpr_lat_even - 60.0 * cpr_lat_odd + 0.5).floor();
+
+    // The decoded latitudes are in the northern hemisphere, with matching latitudes in the
+    // southern hemisphere. As -90, 0 and 90 all encode to 0, a latitude of 0 is moved to the pole
+    // closest to the reference when the reference is more than 45 degrees from the equator.
+    let closest_hemisphere = |lat: f64| {
+        if lat == 0.0 {
+            if reference.latitude < -45.0 {
+                -90.0
+            } else if reference.latitude > 45.0 {
+                90.0
+            } else {
+                0.0
+            }
+        } else if (lat - reference.latitude).abs() <= (lat - 90.0 - reference.latitude).abs() {
+            lat
+        } else {
+            lat - 90.0
+        }
+    };
+    let lat_even = closest_hemisphere(D_LAT_EVEN_SURFACE * (j.rem_euclid(60.0) + cpr_lat_even));
+    let lat_odd = closest_hemisphere(D_LAT_ODD_SURFACE * (j.rem_euclid(59.0) + cpr_lat_odd));
+
+    check_latitudes(lat_even, lat_odd)?;
+
+    let lat = if latest_frame == even_frame {
+        lat_even
+    } else {
+        lat_odd
+    };
+
+    let (p, c) = if latest_frame.parity == Parity::Even {
+        (0, cpr_lon_even)
+    } else {
+        (1, cpr_lon_odd)
+    };
+    let nl = cpr_nl(lat);
+    let ni = cmp::max(nl - p, 1) as f64;
+    let m = (cpr_lon_even * (nl - 1) as f64 - cpr_lon_odd * nl as f64 + 0.5).floor();
+    let lon = (90.0 / ni) * (m.rem_euclid(ni) + c);
+
+    // The decoded longitude matches four longitudes 90 degrees apart
+    let lon = (0..4)
+        .map(|k| (lon + 90.0 * k as f64 + 180.0).rem_euclid(360.0) - 180.0)
+        .min_by(|a, b| {
+            longitude_distance(*a, reference.longitude)
+                .total_cmp(&longitude_distance(*b, reference.longitude))
+        })
+        .unwrap_or(lon);
+
+    Ok(Position {
+        latitude: lat,
+        longitude: lon,
+    })
+}
+
+/// Calculates the position of an aircraft on the surface based on a single frame containing position
+/// information encoded in CPR format. The reference position, such as the location of the receiver or
+/// airport, must be within 45 NM of the aircraft for the decoded position to be correct. Returns `None`
+/// for frames not encoding a surface position, and when the decoded position is more than half a zone
+/// away from the reference.
+pub fn get_surface_local_position(cpr_frame: &CPRFrame, reference: &Position) -> Option<Position> {
+    if !is_surface(cpr_frame) {
+        return None;
+    }
+    get_local_position_in_zone(cpr_frame, reference, 90.0)
+}
+
+// Local decoding finds the zone closest to the reference position and uses it to decode a frame
+// without requiring a frame of opposite parity. Airborne positions use 360 degree zones while
+// surface positions use 90 degree zones. As in readsb, positions more than half a zone away from
+// the reference are rejected.
+fn get_local_position_in_zone(
+    cpr_frame: &CPRFrame,
+    reference: &Position,
+    zone: f64,
+) -> Option<Position> {
+    let i = match cpr_frame.parity {
+        Parity::Even => 0,
+        Parity::Odd => 1,
+    };
+    let (cpr_lat, cpr_lon) = cpr_coordinates(cpr_frame);
+
+    let d_lat = zone / (4.0 * NZ - i as f64);
+    let j = (reference.latitude / d_lat).floor()
+        + (reference.latitude.rem_euclid(d_lat) / d_lat - cpr_lat + 0.5).floor();
+    let lat = d_lat * (j + cpr_lat);
+    if !(-90.0..=90.0).contains(&lat) || (lat - reference.latitude).abs() > d_lat / 2.0 {
+        return None;
+    }
+
+    let d_lon = zone / cmp::max(cpr_nl(lat) - i, 1) as f64;
+    let m = (reference.longitude / d_lon).floor()
+        + (reference.longitude.rem_euclid(d_lon) / d_lon - cpr_lon + 0.5).floor();
+    let lon = (d_lon * (m + cpr_lon) + 180.0).rem_euclid(360.0) - 180.0;
+    if longitude_distance(lon, reference.longitude) > d_lon / 2.0 {
+        return None;
+    }
+
     Some(Position {
         latitude: lat,
         longitude: lon,
     })
 }
 
+// Encoded coordinates as fractions of a zone, which makes coarse TIS-B positions interchangeable
+// with airborne positions.
+fn cpr_coordinates(cpr_frame: &CPRFrame) -> (f64, f64) {
+    let scale = f64::from(1u32 << cpr_frame.encoding.bits());
+    (
+        f64::from(cpr_frame.position.latitude) / scale,
+        f64::from(cpr_frame.position.longitude) / scale,
+    )
+}
+
+// Surface positions are encoded using smaller zones than airborne and coarse TIS-B positions, so
+// they cannot be decoded together.
+fn is_surface(cpr_frame: &CPRFrame) -> bool {
+    cpr_frame.encoding == CPREncoding::Surface
+}
+
+fn check_encodings(cpr_frames: (&CPRFrame, &CPRFrame), surface: bool) -> Result<(), CPRError> {
+    if is_surface(cpr_frames.0) != surface || is_surface(cpr_frames.1) != surface {
+        return Err(CPRError::UnexpectedEncoding);
+    }
+    Ok(())
+}
+
+fn longitude_distance(a: f64, b: f64) -> f64 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+// Both latitudes must be valid and have the same number of longitude zones, otherwise the
+// longitude zone index computed from the pair of frames is meaningless.
+fn check_latitudes(lat_even: f64, lat_odd: f64) -> Result<(), CPRError> {
+    if !(-90.0..=90.0).contains(&lat_even) || !(-90.0..=90.0).contains(&lat_odd) {
+        return Err(CPRError::InvalidLatitude);
+    }
+    if cpr_nl(lat_even) != cpr_nl(lat_odd) {
+        return Err(CPRError::ZoneMismatch);
+    }
+    Ok(())
+}
+
+fn split_parity<'a>(
+    cpr_frames: (&'a CPRFrame, &'a CPRFrame),
+) -> Result<(&'a CPRFrame, &'a CPRFrame), CPRError> {
+    match cpr_frames {
+        (
+            even @ CPRFrame {
+                parity: Parity::Even,
+                ..
+            },
+            odd @ CPRFrame {
+                parity: Parity::Odd,
+                ..
+            },
+        )
+        | (
+            odd @ CPRFrame {
+                parity: Parity::Odd,
+                ..
+            },
+            even @ CPRFrame {
+                parity: Parity::Even,
+                ..
+            },
+        ) => Ok((even, odd)),
+        _ => Err(CPRError::SameParity),
+    }
+}
+
 fn get_lat_lon(lat: f64, cpr_lon_even: f64, cpr_lon_odd: f64, parity: &Parity) -> (f64, f64) {
     let (p, c) = if parity == &Parity::Even {
         (0, cpr_lon_even)
@@ -277,6 +561,7 @@ fn get_lat_lon(lat: f64, cpr_lon_even: f64, cpr_lon_odd: f64, parity: &Parity) -
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::CPRPosition;
     use assert_approx_eq::assert_approx_eq;
 
     #[test]
@@ -304,22 +589,24 @@ mod tests {
     #[test]
     fn cpr_calculate_position() {
         let odd = CPRFrame {
-            position: Position {
-                latitude: 74158.0,
-                longitude: 50194.0,
+            position: CPRPosition {
+                latitude: 74158,
+                longitude: 50194,
             },
             parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
         };
 
         let even = CPRFrame {
-            position: Position {
-                latitude: 93000.0,
-                longitude: 51372.0,
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
             },
             parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
         };
 
-        let position = get_position((&odd, &even)).unwrap();
+        let position = get_position((&odd, &even), None).unwrap();
         assert_approx_eq!(position.latitude, 52.2572021484375);
         assert_approx_eq!(position.longitude, 3.91937255859375);
     }
@@ -327,21 +614,325 @@ mod tests {
     #[test]
     fn cpr_calculate_position_high_lat() {
         let even = CPRFrame {
-            position: Position {
-                latitude: 108011.0,
-                longitude: 110088.0,
+            position: CPRPosition {
+                latitude: 108011,
+                longitude: 110088,
             },
             parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
         };
         let odd = CPRFrame {
-            position: Position {
-                latitude: 75050.0,
-                longitude: 36777.0,
+            position: CPRPosition {
+                latitude: 75050,
+                longitude: 36777,
             },
             parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
         };
-        let position = get_position((&even, &odd)).unwrap();
+        let position = get_position((&even, &odd), None).unwrap();
         assert_approx_eq!(position.latitude, 88.91747426178496);
         assert_approx_eq!(position.longitude, 101.01104736328125);
     }
+
+    #[test]
+    fn cpr_reject_same_parity() {
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        assert_eq!(
+            get_position((&even, &even), None),
+            Err(CPRError::SameParity)
+        );
+    }
+
+    #[test]
+    fn cpr_reject_unexpected_encoding() {
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Surface,
+        };
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 74158,
+                longitude: 50194,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
+        };
+        assert_eq!(
+            get_position((&even, &odd), None),
+            Err(CPRError::UnexpectedEncoding)
+        );
+        assert_eq!(
+            get_local_position(
+                &even,
+                &Position {
+                    latitude: 52.0,
+                    longitude: 4.0
+                }
+            ),
+            None
+        );
+        assert_eq!(
+            get_surface_local_position(
+                &odd,
+                &Position {
+                    latitude: 52.0,
+                    longitude: 4.0
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn cpr_calculate_coarse_position() {
+        // Same position as cpr_calculate_position, truncated to 12 bits
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 74158 >> 5,
+                longitude: 50194 >> 5,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Coarse,
+        };
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000 >> 5,
+                longitude: 51372 >> 5,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Coarse,
+        };
+        let position = get_position((&odd, &even), None).unwrap();
+        assert!((position.latitude - 52.2572021484375).abs() < 0.01);
+        assert!((position.longitude - 3.91937255859375).abs() < 0.01);
+        assert_eq!(fixed::get_position((&odd, &even), None), Ok(position));
+    }
+
+    #[test]
+    fn cpr_reject_zone_mismatch() {
+        // Latitude 10.4710 (NL 58) in the even frame and 10.4700 (NL 59) in the odd frame
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 97670,
+                longitude: 29127,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 93837,
+                longitude: 29127,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
+        };
+        assert_eq!(
+            get_position((&even, &odd), None),
+            Err(CPRError::ZoneMismatch)
+        );
+    }
+
+    #[test]
+    fn cpr_reject_frames_too_far_apart() {
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 74158,
+                longitude: 50194,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
+        };
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        let first = SystemTime::UNIX_EPOCH;
+
+        let receive_times = ReceiveTimes::new(first, first + Duration::from_secs(10));
+        assert!(get_position((&odd, &even), Some(&receive_times)).is_ok());
+
+        let receive_times = ReceiveTimes::new(first, first + Duration::from_secs(11));
+        assert_eq!(
+            get_position((&odd, &even), Some(&receive_times)),
+            Err(CPRError::FramesTooFarApart)
+        );
+
+        let receive_times = ReceiveTimes {
+            max_interval: Some(Duration::from_secs(20)),
+            ..ReceiveTimes::new(first, first + Duration::from_secs(11))
+        };
+        assert!(get_position((&odd, &even), Some(&receive_times)).is_ok());
+    }
+
+    #[test]
+    fn cpr_calculate_local_position() {
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 74158,
+                longitude: 50194,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Airborne,
+        };
+        let reference = Position {
+            latitude: 52.258,
+            longitude: 3.918,
+        };
+
+        let position = get_local_position(&even, &reference).unwrap();
+        assert_approx_eq!(position.latitude, 52.2572021484375);
+        assert_approx_eq!(position.longitude, 3.91937255859375);
+
+        let position = get_local_position(&odd, &reference).unwrap();
+        let global = get_position((&even, &odd), None).unwrap();
+        assert_approx_eq!(position.latitude, global.latitude);
+        assert_approx_eq!(position.longitude, global.longitude);
+    }
+
+    #[test]
+    fn cpr_calculate_local_position_negative_reference() {
+        let frame = CPRFrame {
+            position: CPRPosition {
+                latitude: 93000,
+                longitude: 51372,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        let reference = Position {
+            latitude: -33.9,
+            longitude: -70.8,
+        };
+        let position = get_local_position(&frame, &reference).unwrap();
+        assert!((position.latitude - reference.latitude).abs() < 3.0);
+        assert!((position.longitude - reference.longitude).abs() < 6.0);
+    }
+
+    #[test]
+    fn cpr_calculate_local_position_across_antimeridian() {
+        // Encoded at latitude 0 and longitude -179.95
+        let frame = CPRFrame {
+            position: CPRPosition {
+                latitude: 0,
+                longitude: 66610,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Airborne,
+        };
+        let reference = Position {
+            latitude: 0.0,
+            longitude: 179.95,
+        };
+        let position = get_local_position(&frame, &reference).unwrap();
+        assert_approx_eq!(position.latitude, 0.0);
+        assert_approx_eq!(position.longitude, -179.95, 1e-4);
+    }
+
+    #[test]
+    fn cpr_calculate_surface_position() {
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 115609,
+                longitude: 116941,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Surface,
+        };
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 39199,
+                longitude: 110269,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Surface,
+        };
+        let reference = Position {
+            latitude: 51.990,
+            longitude: 4.375,
+        };
+        let position = get_surface_position((&even, &odd), &reference, None).unwrap();
+        assert_approx_eq!(position.latitude, 52.320607072215964);
+        assert_approx_eq!(position.longitude, 4.734734671456465);
+
+        // Surface frames may be received further apart than airborne frames
+        let first = SystemTime::UNIX_EPOCH;
+        let receive_times = ReceiveTimes::new(first, first + Duration::from_secs(25));
+        assert!(get_surface_position((&even, &odd), &reference, Some(&receive_times)).is_ok());
+        let receive_times = ReceiveTimes::new(first, first + Duration::from_secs(26));
+        assert_eq!(
+            get_surface_position((&even, &odd), &reference, Some(&receive_times)),
+            Err(CPRError::FramesTooFarApart)
+        );
+
+        // A reference in the southern hemisphere selects the matching southern quadrant
+        let reference = Position {
+            latitude: -30.0,
+            longitude: 4.375,
+        };
+        let position = get_surface_position((&even, &odd), &reference, None).unwrap();
+        assert_approx_eq!(position.latitude, 52.320607072215964 - 90.0);
+
+        // A latitude of 0 also encodes the poles
+        let even = CPRFrame {
+            position: CPRPosition {
+                latitude: 0,
+                longitude: 0,
+            },
+            parity: Parity::Even,
+            encoding: CPREncoding::Surface,
+        };
+        let odd = CPRFrame {
+            parity: Parity::Odd,
+            ..even.clone()
+        };
+        let reference = Position {
+            latitude: 80.0,
+            longitude: 10.0,
+        };
+        let position = get_surface_position((&even, &odd), &reference, None).unwrap();
+        assert_approx_eq!(position.latitude, 90.0);
+    }
+
+    #[test]
+    fn cpr_calculate_surface_local_position() {
+        let odd = CPRFrame {
+            position: CPRPosition {
+                latitude: 39199,
+                longitude: 110269,
+            },
+            parity: Parity::Odd,
+            encoding: CPREncoding::Surface,
+        };
+        let reference = Position {
+            latitude: 51.990,
+            longitude: 4.375,
+        };
+        let position = get_surface_local_position(&odd, &reference).unwrap();
+        assert_approx_eq!(position.latitude, 52.320607072215964);
+        assert_approx_eq!(position.longitude, 4.734734671456465);
+    }
 }
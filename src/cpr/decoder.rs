@@ -0,0 +1,461 @@
+use super::{
+    get_local_position, get_position, get_surface_local_position, get_surface_position,
+    longitude_distance, ReceiveTimes,
+};
+use crate::types::{
+    ADSBMessageKind, CPREncoding, CPRFrame, ICAOAddress, Message, MessageKind, Parity, Position,
+};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Maximum time since the reference position of an aircraft was last confirmed by a global decode
+/// for it to be used for local decoding.
+pub const LOCAL_REFERENCE_MAX_AGE: Duration = Duration::from_secs(60);
+
+// Maximum difference in degrees between the global and local decoding of the same frame for them to
+// be considered consistent.
+const CONSISTENCY_TOLERANCE: f64 = 0.001;
+
+/// Method used to decode a position.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DecodingMethod {
+    /// Decoded from a pair of frames of opposite parity
+    Global,
+    /// Decoded from a single frame relative to the last known position of the aircraft
+    Local,
+}
+
+/// Position decoded by a [`CPRDecoder`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct DecodedPosition {
+    /// Aircraft ICAO address
+    pub icao_address: ICAOAddress,
+    /// Decoded position
+    pub position: Position,
+    /// Encoding of the frame the position was decoded from
+    pub encoding: CPREncoding,
+    /// Method used to decode the position
+    pub method: DecodingMethod,
+    /// Time at which the latest frame used to decode the position was received
+    pub time: SystemTime,
+}
+
+// Position of an aircraft along with the time it was last decoded or confirmed globally.
+#[derive(Debug, Clone)]
+struct Reference {
+    position: Position,
+    time: SystemTime,
+}
+
+#[derive(Debug, Default)]
+struct AircraftState {
+    even: Option<(CPRFrame, SystemTime)>,
+    odd: Option<(CPRFrame, SystemTime)>,
+    // Confirmed position used as the reference for local decoding
+    reference: Option<Reference>,
+    // Globally decoded position waiting to be confirmed
+    candidate: Option<Reference>,
+    last_position: Option<Position>,
+}
+
+/// Decodes the positions of multiple aircraft from a stream of messages, keeping track of the latest
+/// frames received from each aircraft.
+///
+/// Once an aircraft has a reliable reference position, every frame is decoded locally relative to
+/// it. Global decoding of the latest pair of frames received close enough together is only used to
+/// find and reconfirm that reference:
+/// - A globally decoded position becomes the reference once the local decoding of the latest frame
+///   relative to the location of the receiver, or relative to a previous global position decoded
+///   from different frames, is consistent with it. A single corrupted frame with a valid CRC
+///   therefore cannot become the reference.
+/// - The reference is reconfirmed whenever a global decode is consistent with the local one, and is
+///   no longer used if it was not reconfirmed in the last [`LOCAL_REFERENCE_MAX_AGE`].
+#[derive(Debug, Default)]
+pub struct CPRDecoder {
+    reference: Option<Position>,
+    aircraft: HashMap<ICAOAddress, AircraftState>,
+}
+
+impl CPRDecoder {
+    /// Creates a decoder without a reference position. Surface positions can only be decoded once
+    /// the aircraft has a known position.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a decoder using the location of the receiver as the reference position to globally
+    /// decode surface positions, and to confirm the first position of aircraft within half a zone
+    /// of the receiver (about 180 NM when airborne and 45 NM on the surface).
+    pub fn with_reference(reference: Position) -> Self {
+        CPRDecoder {
+            reference: Some(reference),
+            aircraft: HashMap::new(),
+        }
+    }
+
+    /// Processes a message received at the given time, returning the decoded position of the
+    /// aircraft if the message contains a position that could be reliably decoded. Messages without
+    /// position information or with an invalid CRC are ignored.
+    pub fn decode(&mut self, message: &Message, time: SystemTime) -> Option<DecodedPosition> {
+        let (icao_address, cpr_frame) = match &message.kind {
+            MessageKind::ADSBMessage {
+                icao_address,
+                kind:
+                    ADSBMessageKind::AirbornePosition { cpr_frame, .. }
+                    | ADSBMessageKind::SurfacePosition { cpr_frame, .. },
+                crc: true,
+                ..
+            } => (*icao_address, cpr_frame),
+            _ => return None,
+        };
+
+        let receiver = self.reference.as_ref();
+        let state = self.aircraft.entry(icao_address).or_default();
+        let (latest, other) = match cpr_frame.parity {
+            Parity::Even => (&mut state.even, &state.odd),
+            Parity::Odd => (&mut state.odd, &state.even),
+        };
+        *latest = Some((cpr_frame.clone(), time));
+
+        let surface = cpr_frame.encoding == CPREncoding::Surface;
+        let local_position = |reference: &Position| {
+            if surface {
+                get_surface_local_position(cpr_frame, reference)
+            } else {
+                get_local_position(cpr_frame, reference)
+            }
+        };
+        let recent = |reference: &Option<Reference>| {
+            reference
+                .clone()
+                .filter(|reference| within(reference.time, time, LOCAL_REFERENCE_MAX_AGE))
+        };
+        let reference = recent(&state.reference);
+        let candidate = recent(&state.candidate);
+
+        let global = other.as_ref().and_then(|(other_frame, other_time)| {
+            let receive_times = ReceiveTimes::new(*other_time, time);
+            let position = if surface {
+                let surface_reference = reference
+                    .as_ref()
+                    .or(candidate.as_ref())
+                    .map(|reference| &reference.position)
+                    .or(receiver)?;
+                get_surface_position(
+                    (other_frame, cpr_frame),
+                    surface_reference,
+                    Some(&receive_times),
+                )
+            } else {
+                get_position((other_frame, cpr_frame), Some(&receive_times))
+            };
+            Some((position.ok()?, *other_time))
+        });
+
+        let (position, method) = match reference {
+            Some(mut reference) => {
+                let position = local_position(&reference.position)?;
+                match global {
+                    Some((global, _)) if same_position(&position, &global) => {
+                        reference.time = time;
+                    }
+                    Some((global, _)) => {
+                        state.candidate = Some(Reference {
+                            position: global,
+                            time,
+                        });
+                    }
+                    None => {}
+                }
+                reference.position = position.clone();
+                state.reference = Some(reference);
+                (position, DecodingMethod::Local)
+            }
+            None => {
+                let (global, other_time) = global?;
+                let consistent = |reference: &Position| {
+                    local_position(reference).is_some_and(|local| same_position(&local, &global))
+                };
+                let candidate_consistent = candidate
+                    .as_ref()
+                    .is_some_and(|candidate| consistent(&candidate.position));
+                // A pair sharing a frame with the pair the candidate was decoded from would repeat
+                // the error of a corrupted frame, so it cannot confirm the candidate
+                let independent = candidate
+                    .as_ref()
+                    .is_some_and(|candidate| other_time > candidate.time);
+                if !(candidate_consistent && independent || receiver.is_some_and(consistent)) {
+                    if !candidate_consistent {
+                        state.candidate = Some(Reference {
+                            position: global,
+                            time,
+                        });
+                    }
+                    return None;
+                }
+                state.reference = Some(Reference {
+                    position: global.clone(),
+                    time,
+                });
+                state.candidate = None;
+                (global, DecodingMethod::Global)
+            }
+        };
+
+        state.last_position = Some(position.clone());
+        Some(DecodedPosition {
+            icao_address,
+            position,
+            encoding: cpr_frame.encoding,
+            method,
+            time,
+        })
+    }
+
+    /// Returns the last decoded position of an aircraft.
+    pub fn last_position(&self, icao_address: &ICAOAddress) -> Option<&Position> {
+        self.aircraft.get(icao_address)?.last_position.as_ref()
+    }
+
+    /// Discards the last decoded position of an aircraft, such as after it was rejected by a
+    /// [`PositionFilter`](super::PositionFilter). Its reference position can no longer be used
+    /// for local decoding until confirmed again by global decoding, and is replaced if the next
+    /// global decodes are inconsistent with it.
+    pub fn reject(&mut self, icao_address: &ICAOAddress) {
+        if let Some(state) = self.aircraft.get_mut(icao_address) {
+            state.last_position = None;
+            if let Some(reference) = state.reference.take() {
+                state.candidate = Some(reference);
+            }
+        }
+    }
+
+    /// Forgets aircraft from which no position frames were received in the given interval before
+    /// `time`.
+    pub fn remove_inactive(&mut self, time: SystemTime, max_age: Duration) {
+        self.aircraft.retain(|_, state| {
+            [&state.even, &state.odd]
+                .iter()
+                .filter_map(|frame| frame.as_ref())
+                .any(|(_, frame_time)| within(*frame_time, time, max_age))
+        });
+    }
+}
+
+fn same_position(a: &Position, b: &Position) -> bool {
+    (a.latitude - b.latitude).abs() <= CONSISTENCY_TOLERANCE
+        && longitude_distance(a.longitude, b.longitude) <= CONSISTENCY_TOLERANCE
+}
+
+fn within(earlier: SystemTime, later: SystemTime, max_age: Duration) -> bool {
+    match later.duration_since(earlier) {
+        Ok(age) => age <= max_age,
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_binary;
+    use crate::types::CPRPosition;
+    use assert_approx_eq::assert_approx_eq;
+
+    const EVEN: &[u8] = b"\x8D\x40\x62\x1D\x58\xC3\x82\xD6\x90\xC8\xAC\x28\x63\xA7";
+    const ODD: &[u8] = b"\x8D\x40\x62\x1D\x58\xC3\x86\x43\x5C\xC4\x12\x69\x2A\xD6";
+
+    fn message(data: &[u8]) -> Message {
+        parse_binary(data).unwrap().0
+    }
+
+    fn surface_message(latitude: u32, longitude: u32, parity: Parity) -> Message {
+        Message {
+            downlink_format: 17,
+            kind: MessageKind::ADSBMessage {
+                capability: 5,
+                icao_address: ICAOAddress(0x48, 0x41, 0x75),
+                type_code: 7,
+                kind: ADSBMessageKind::SurfacePosition {
+                    movement: crate::types::Movement::Stopped,
+                    ground_track: None,
+                    utc_synchronized: false,
+                    cpr_frame: CPRFrame {
+                        position: CPRPosition {
+                            latitude,
+                            longitude,
+                        },
+                        parity,
+                        encoding: CPREncoding::Surface,
+                    },
+                },
+                crc: true,
+            },
+        }
+    }
+
+    // Odd frame with a bit error in the latitude, decoding to a valid position around 40.25, 3.14
+    fn corrupted_odd() -> Message {
+        let mut message = message(ODD);
+        if let MessageKind::ADSBMessage {
+            kind: ADSBMessageKind::AirbornePosition { cpr_frame, .. },
+            ..
+        } = &mut message.kind
+        {
+            cpr_frame.position.latitude ^= 1 << 12;
+        }
+        message
+    }
+
+    fn seconds(s: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(s)
+    }
+
+    #[test]
+    fn decode_global_then_local() {
+        let mut decoder = CPRDecoder::new();
+
+        // The first global position is only returned once confirmed by an independent pair
+        assert_eq!(decoder.decode(&message(ODD), seconds(0)), None);
+        assert_eq!(decoder.decode(&message(EVEN), seconds(1)), None);
+        assert_eq!(decoder.decode(&message(ODD), seconds(2)), None);
+        let decoded = decoder.decode(&message(EVEN), seconds(3)).unwrap();
+        assert_eq!(decoded.icao_address, ICAOAddress(0x40, 0x62, 0x1D));
+        assert_eq!(decoded.method, DecodingMethod::Global);
+        assert_eq!(decoded.encoding, CPREncoding::Airborne);
+        assert_approx_eq!(decoded.position.latitude, 52.2572021484375);
+        assert_approx_eq!(decoded.position.longitude, 3.91937255859375);
+
+        // Once confirmed, positions are decoded locally even when a recent pair is available
+        let decoded = decoder.decode(&message(ODD), seconds(4)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        assert_approx_eq!(decoded.position.latitude, 52.26578017412606);
+        assert_approx_eq!(decoded.position.longitude, 3.938912527901786);
+        let decoded = decoder.decode(&message(EVEN), seconds(5)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        assert_approx_eq!(decoded.position.latitude, 52.2572021484375);
+        assert_approx_eq!(decoded.position.longitude, 3.91937255859375);
+        assert_eq!(
+            decoder.last_position(&decoded.icao_address),
+            Some(&decoded.position)
+        );
+
+        // Consistent global decodes keep reconfirming the reference
+        for s in 6..100 {
+            let frame = if s % 2 == 0 { ODD } else { EVEN };
+            let decoded = decoder.decode(&message(frame), seconds(s)).unwrap();
+            assert_eq!(decoded.method, DecodingMethod::Local);
+        }
+    }
+
+    #[test]
+    fn decode_confirmed_by_receiver() {
+        let mut decoder = CPRDecoder::with_reference(Position {
+            latitude: 52.0,
+            longitude: 4.0,
+        });
+        assert_eq!(decoder.decode(&message(ODD), seconds(0)), None);
+        let decoded = decoder.decode(&message(EVEN), seconds(1)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Global);
+        assert_approx_eq!(decoded.position.latitude, 52.2572021484375);
+        let decoded = decoder.decode(&message(ODD), seconds(2)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+
+        // A receiver more than half a zone away cannot confirm the position
+        let mut decoder = CPRDecoder::with_reference(Position {
+            latitude: 40.0,
+            longitude: 4.0,
+        });
+        assert_eq!(decoder.decode(&message(ODD), seconds(0)), None);
+        assert_eq!(decoder.decode(&message(EVEN), seconds(1)), None);
+    }
+
+    #[test]
+    fn decode_requires_recent_frames() {
+        let mut decoder = CPRDecoder::new();
+
+        decoder.decode(&message(ODD), seconds(0));
+        decoder.decode(&message(EVEN), seconds(1));
+        decoder.decode(&message(ODD), seconds(2));
+        decoder.decode(&message(EVEN), seconds(3));
+
+        // The reference is aged from its last global confirmation, not from local decodes
+        let decoded = decoder.decode(&message(ODD), seconds(30)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        let decoded = decoder.decode(&message(ODD), seconds(63)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        assert_eq!(decoder.decode(&message(ODD), seconds(64)), None);
+
+        // A stale reference falls back to global decoding, which must be confirmed again
+        assert_eq!(decoder.decode(&message(EVEN), seconds(65)), None);
+        assert_eq!(decoder.decode(&message(ODD), seconds(66)), None);
+        let decoded = decoder.decode(&message(EVEN), seconds(67)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Global);
+
+        decoder.remove_inactive(seconds(127), Duration::from_secs(30));
+        assert_eq!(decoder.last_position(&decoded.icao_address), None);
+    }
+
+    #[test]
+    fn decode_rejects_unconfirmed_reference() {
+        let mut decoder = CPRDecoder::new();
+
+        // A corrupted frame decodes to a wrong global position, which is not confirmed by the
+        // following pairs and is replaced
+        assert_eq!(decoder.decode(&corrupted_odd(), seconds(0)), None);
+        assert_eq!(decoder.decode(&message(EVEN), seconds(1)), None);
+        assert_eq!(decoder.decode(&message(ODD), seconds(2)), None);
+        assert_eq!(decoder.decode(&message(EVEN), seconds(3)), None);
+        let decoded = decoder.decode(&message(ODD), seconds(4)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Global);
+        assert_approx_eq!(decoded.position.latitude, 52.26578017412606);
+
+        // Later corrupted frames are decoded locally near the reference rather than globally
+        let decoded = decoder.decode(&corrupted_odd(), seconds(5)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        assert!((decoded.position.latitude - 52.26578017412606).abs() < 0.5);
+        let decoded = decoder.decode(&message(EVEN), seconds(6)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+        assert_approx_eq!(decoded.position.latitude, 52.2572021484375);
+    }
+
+    #[test]
+    fn reject_decoded_position() {
+        let mut decoder = CPRDecoder::new();
+        decoder.decode(&message(ODD), seconds(0));
+        decoder.decode(&message(EVEN), seconds(1));
+        decoder.decode(&message(ODD), seconds(2));
+        let decoded = decoder.decode(&message(EVEN), seconds(3)).unwrap();
+
+        // A rejected position is no longer used as the reference for local decoding
+        decoder.reject(&decoded.icao_address);
+        assert_eq!(decoder.last_position(&decoded.icao_address), None);
+        assert_eq!(decoder.decode(&message(ODD), seconds(20)), None);
+        let decoded = decoder.decode(&message(EVEN), seconds(21)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Global);
+    }
+
+    #[test]
+    fn decode_surface_with_reference() {
+        let even = surface_message(115609, 116941, Parity::Even);
+        let odd = surface_message(39199, 110269, Parity::Odd);
+
+        let mut decoder = CPRDecoder::new();
+        decoder.decode(&even, seconds(0));
+        assert_eq!(decoder.decode(&odd, seconds(1)), None);
+        assert_eq!(decoder.decode(&even, seconds(2)), None);
+        assert_eq!(decoder.decode(&odd, seconds(3)), None);
+
+        let mut decoder = CPRDecoder::with_reference(Position {
+            latitude: 51.990,
+            longitude: 4.375,
+        });
+        decoder.decode(&even, seconds(0));
+        let decoded = decoder.decode(&odd, seconds(1)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Global);
+        assert_eq!(decoded.encoding, CPREncoding::Surface);
+        assert_approx_eq!(decoded.position.latitude, 52.320607072215964);
+        assert_approx_eq!(decoded.position.longitude, 4.734734671456465);
+        let decoded = decoder.decode(&even, seconds(2)).unwrap();
+        assert_eq!(decoded.method, DecodingMethod::Local);
+    }
+}
@@ -0,0 +1,358 @@
+use super::DecodedPosition;
+use crate::types::{
+    ADSBMessageKind, ICAOAddress, Message, MessageKind, Movement, Position, Velocity,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+const EARTH_RADIUS_NM: f64 = 3440.065;
+
+/// Maximum speed in knots assumed for an aircraft that has not reported its ground speed.
+pub const DEFAULT_MAX_SPEED: f64 = 1000.0;
+/// Margin in knots added to the reported ground speed of an aircraft, allowing for speed changes
+/// and velocity reports received less often than positions.
+pub const SPEED_MARGIN: f64 = 100.0;
+/// Distance in nautical miles an aircraft is always allowed to move between positions, covering
+/// the resolution of CPR encoding and timing jitter between closely received frames.
+pub const DISTANCE_MARGIN: f64 = 0.5;
+/// Number of consecutive positions of an aircraft rejected for implying a too high speed after
+/// which its last accepted position is assumed to be wrong and is forgotten.
+pub const MAX_CONSECUTIVE_REJECTIONS: u32 = 3;
+
+/// Reason a decoded position was rejected by a [`PositionFilter`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum RejectionReason {
+    /// The position is further from the receiver than the maximum range
+    OutOfRange {
+        /// Distance from the receiver to the position in nautical miles
+        distance: f64,
+        /// Maximum range of the receiver in nautical miles
+        max_range: f64,
+    },
+    /// Moving from the previous position would require a higher speed than allowed
+    SpeedTooHigh {
+        /// Speed implied by moving from the previous position in knots
+        speed: f64,
+        /// Maximum speed allowed for the aircraft in knots
+        max_speed: f64,
+    },
+}
+
+impl fmt::Display for RejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RejectionReason::OutOfRange {
+                distance,
+                max_range,
+            } => write!(
+                f,
+                "position is {:.1} NM from the receiver, beyond the maximum range of {:.1} NM",
+                distance, max_range
+            ),
+            RejectionReason::SpeedTooHigh { speed, max_speed } => write!(
+                f,
+                "position implies a speed of {:.0} kt, above the maximum of {:.0} kt",
+                speed, max_speed
+            ),
+        }
+    }
+}
+
+impl Error for RejectionReason {}
+
+#[derive(Debug, Default)]
+struct AircraftState {
+    last_position: Option<(Position, SystemTime)>,
+    ground_speed: Option<f64>,
+    rejections: u32,
+    last_checked: Option<SystemTime>,
+}
+
+/// Rejects implausible positions, such as those resulting from corrupted frames with a valid CRC.
+/// Each position is checked against the last accepted position of the aircraft, using the ground
+/// speed it last reported to bound the distance it can have travelled, and optionally against the
+/// maximum range of the receiver.
+///
+/// If the first accepted position of an aircraft was wrong, its actual positions are rejected until
+/// [`MAX_CONSECUTIVE_REJECTIONS`] are rejected in a row, after which the next position is accepted.
+/// Rejected positions should also be passed to [`CPRDecoder::reject`](super::CPRDecoder::reject)
+/// so they are not used as the reference for decoding later positions, and
+/// [`remove_inactive`](Self::remove_inactive) should be called periodically to forget aircraft that
+/// are no longer received.
+#[derive(Debug, Default)]
+pub struct PositionFilter {
+    receiver: Option<(Position, f64)>,
+    aircraft: HashMap<ICAOAddress, AircraftState>,
+}
+
+impl PositionFilter {
+    /// Creates a filter that only checks positions against previous positions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a filter that also rejects positions further than `max_range` nautical miles from
+    /// the location of the receiver.
+    pub fn with_receiver_range(receiver: Position, max_range: f64) -> Self {
+        PositionFilter {
+            receiver: Some((receiver, max_range)),
+            aircraft: HashMap::new(),
+        }
+    }
+
+    /// Records the ground speed reported in an airborne velocity or surface position message.
+    /// Other messages are ignored.
+    pub fn update_velocity(&mut self, message: &Message) {
+        let (icao_address, ground_speed) = match &message.kind {
+            MessageKind::ADSBMessage {
+                icao_address,
+                kind,
+                crc: true,
+                ..
+            } => match kind {
+                ADSBMessageKind::AirborneVelocity {
+                    velocity:
+                        Velocity::GroundSpeed {
+                            ground_speed: Some(ground_speed),
+                            ..
+                        },
+                    ..
+                } => (icao_address, *ground_speed),
+                ADSBMessageKind::SurfacePosition { movement, .. } => match movement {
+                    Movement::Stopped => (icao_address, 0.0),
+                    Movement::GroundSpeed(ground_speed) => (icao_address, *ground_speed),
+                    _ => return,
+                },
+                _ => return,
+            },
+            _ => return,
+        };
+        self.aircraft.entry(*icao_address).or_default().ground_speed = Some(ground_speed);
+    }
+
+    /// Checks a decoded position, remembering it as the last position of the aircraft if it is
+    /// accepted. The last position of the aircraft is forgotten after
+    /// [`MAX_CONSECUTIVE_REJECTIONS`] positions were rejected in a row for implying a too high
+    /// speed.
+    pub fn check(&mut self, decoded: &DecodedPosition) -> Result<(), RejectionReason> {
+        if let Some((receiver, max_range)) = &self.receiver {
+            let distance = distance(receiver, &decoded.position);
+            if distance > *max_range {
+                return Err(RejectionReason::OutOfRange {
+                    distance,
+                    max_range: *max_range,
+                });
+            }
+        }
+
+        let state = self.aircraft.entry(decoded.icao_address).or_default();
+        state.last_checked = Some(decoded.time);
+        if let Some((last_position, last_time)) = &state.last_position {
+            let hours = decoded
+                .time
+                .duration_since(*last_time)
+                .map_or(0.0, |elapsed| elapsed.as_secs_f64() / 3600.0);
+            let max_speed = state
+                .ground_speed
+                .map_or(DEFAULT_MAX_SPEED, |ground_speed| {
+                    ground_speed + SPEED_MARGIN
+                });
+            let distance = distance(last_position, &decoded.position);
+            if distance > max_speed * hours + DISTANCE_MARGIN {
+                state.rejections += 1;
+                if state.rejections >= MAX_CONSECUTIVE_REJECTIONS {
+                    state.last_position = None;
+                    state.rejections = 0;
+                }
+                return Err(RejectionReason::SpeedTooHigh {
+                    speed: if hours > 0.0 {
+                        distance / hours
+                    } else {
+                        f64::INFINITY
+                    },
+                    max_speed,
+                });
+            }
+        }
+
+        state.last_position = Some((decoded.position.clone(), decoded.time));
+        state.rejections = 0;
+        Ok(())
+    }
+
+    /// Forgets aircraft of which no position was checked in the given interval before `time`,
+    /// along with the ground speed they reported.
+    pub fn remove_inactive(&mut self, time: SystemTime, max_age: Duration) {
+        self.aircraft.retain(|_, state| {
+            state
+                .last_checked
+                .is_some_and(|last_checked| match time.duration_since(last_checked) {
+                    Ok(age) => age <= max_age,
+                    Err(_) => true,
+                })
+        });
+    }
+}
+
+// Great circle distance in nautical miles
+fn distance(a: &Position, b: &Position) -> f64 {
+    let (lat_a, lat_b) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat_b - lat_a;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+    let h = (d_lat / 2.0).sin().powi(2) + lat_a.cos() * lat_b.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_NM * h.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpr::DecodingMethod;
+    use crate::types::{CPREncoding, VerticalRateSource};
+    use assert_approx_eq::assert_approx_eq;
+
+    const ICAO_ADDRESS: ICAOAddress = ICAOAddress(0x40, 0x62, 0x1D);
+
+    fn decoded(latitude: f64, longitude: f64, time: SystemTime) -> DecodedPosition {
+        DecodedPosition {
+            icao_address: ICAO_ADDRESS,
+            position: Position {
+                latitude,
+                longitude,
+            },
+            encoding: CPREncoding::Airborne,
+            method: DecodingMethod::Global,
+            time,
+        }
+    }
+
+    fn velocity(ground_speed: f64) -> Message {
+        Message {
+            downlink_format: 17,
+            kind: MessageKind::ADSBMessage {
+                capability: 5,
+                icao_address: ICAO_ADDRESS,
+                type_code: 19,
+                kind: ADSBMessageKind::AirborneVelocity {
+                    velocity: Velocity::GroundSpeed {
+                        east_west: Some(ground_speed as i16),
+                        north_south: Some(0),
+                        heading: Some(90.0),
+                        ground_speed: Some(ground_speed),
+                        saturated: false,
+                    },
+                    supersonic: false,
+                    intent_change: false,
+                    ifr_capability: false,
+                    navigation_accuracy: 0,
+                    vertical_rate: Some(0),
+                    vertical_rate_saturated: false,
+                    vertical_rate_source: VerticalRateSource::BarometricPressureAltitude,
+                    gnss_baro_difference: None,
+                },
+                crc: true,
+            },
+        }
+    }
+
+    #[test]
+    fn great_circle_distance() {
+        let a = Position {
+            latitude: 0.0,
+            longitude: 0.0,
+        };
+        let b = Position {
+            latitude: 1.0,
+            longitude: 0.0,
+        };
+        assert_approx_eq!(distance(&a, &b), 60.04, 0.01);
+    }
+
+    #[test]
+    fn reject_position_out_of_range() {
+        let receiver = Position {
+            latitude: 52.0,
+            longitude: 4.0,
+        };
+        let mut filter = PositionFilter::with_receiver_range(receiver, 250.0);
+        let t = SystemTime::UNIX_EPOCH;
+        assert_eq!(filter.check(&decoded(52.2572, 3.9193, t)), Ok(()));
+        match filter.check(&decoded(58.0, 4.0, t)) {
+            Err(RejectionReason::OutOfRange { distance, .. }) => assert!(distance > 350.0),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn reject_position_implying_excessive_speed() {
+        let mut filter = PositionFilter::new();
+        let t = SystemTime::UNIX_EPOCH;
+        assert_eq!(filter.check(&decoded(52.0, 4.0, t)), Ok(()));
+
+        // Half a degree of latitude in 10 seconds is around 10800 knots
+        let result = filter.check(&decoded(52.5, 4.0, t + Duration::from_secs(10)));
+        assert!(matches!(
+            result,
+            Err(RejectionReason::SpeedTooHigh { max_speed, .. }) if max_speed == DEFAULT_MAX_SPEED
+        ));
+
+        // Rejected positions do not replace the last accepted position
+        assert_eq!(
+            filter.check(&decoded(52.01, 4.0, t + Duration::from_secs(10))),
+            Ok(())
+        );
+
+        // The reported ground speed bounds the implied speed: 0.1 degrees in 60 seconds is
+        // around 360 knots
+        filter.update_velocity(&velocity(200.0));
+        let result = filter.check(&decoded(52.11, 4.0, t + Duration::from_secs(70)));
+        assert!(matches!(
+            result,
+            Err(RejectionReason::SpeedTooHigh { max_speed, .. }) if max_speed == 300.0
+        ));
+        filter.update_velocity(&velocity(350.0));
+        assert_eq!(
+            filter.check(&decoded(52.11, 4.0, t + Duration::from_secs(70))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn recover_from_wrong_first_position() {
+        let mut filter = PositionFilter::new();
+        let t = SystemTime::UNIX_EPOCH;
+        assert_eq!(filter.check(&decoded(47.0, 4.0, t)), Ok(()));
+
+        for s in 1..=MAX_CONSECUTIVE_REJECTIONS {
+            let time = t + Duration::from_secs(u64::from(s));
+            assert!(matches!(
+                filter.check(&decoded(52.0, 4.0, time)),
+                Err(RejectionReason::SpeedTooHigh { .. })
+            ));
+        }
+        let time = t + Duration::from_secs(u64::from(MAX_CONSECUTIVE_REJECTIONS) + 1);
+        assert_eq!(filter.check(&decoded(52.0, 4.0, time)), Ok(()));
+        assert_eq!(
+            filter.check(&decoded(52.0, 4.0, time + Duration::from_secs(1))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn remove_inactive_aircraft() {
+        let mut filter = PositionFilter::new();
+        let t = SystemTime::UNIX_EPOCH;
+        filter.update_velocity(&velocity(200.0));
+        assert_eq!(filter.check(&decoded(52.0, 4.0, t)), Ok(()));
+
+        filter.remove_inactive(t + Duration::from_secs(30), Duration::from_secs(60));
+        assert_eq!(filter.aircraft.len(), 1);
+        filter.remove_inactive(t + Duration::from_secs(61), Duration::from_secs(60));
+        assert!(filter.aircraft.is_empty());
+
+        // The aircraft is checked as if it had never been seen
+        let time = t + Duration::from_secs(62);
+        assert_eq!(filter.check(&decoded(58.0, 4.0, time)), Ok(()));
+    }
+}
@@ -0,0 +1,631 @@
+//! Decode CPR positions using integer arithmetic, producing the same output as the decoder in
+//! [readsb](https://github.com/wiedehopf/readsb/blob/dev/cpr.c).
+//!
+//! Zone indices are computed using integers and latitudes and longitudes are kept as fixed-point
+//! values with 17 fractional bits until they are scaled to degrees. This avoids the pitfalls of
+//! floating point remainders with negative zone indices in the decoder in the parent module.
+
+use super::{
+    check_encodings, check_latitudes, cpr_nl, is_surface, split_parity, CPRError, ReceiveTimes,
+};
+use crate::types::{CPRFrame, Parity, Position};
+use std::cmp;
+
+const CPR_MAX: i64 = 1 << 17;
+const CPR_SCALE: f64 = 131_072.0;
+
+// Encoded coordinates as fixed-point values with 17 fractional bits.
+fn cpr_coordinates(cpr_frame: &CPRFrame) -> (i64, i64) {
+    let shift = 17 - cpr_frame.encoding.bits();
+    (
+        i64::from(cpr_frame.position.latitude) << shift,
+        i64::from(cpr_frame.position.longitude) << shift,
+    )
+}
+
+// Number of longitude zones for a latitude and parity.
+fn cpr_n(lat: f64, parity: &Parity) -> i64 {
+    let nl = cpr_nl(lat) as i64;
+    match parity {
+        Parity::Even => cmp::max(nl, 1),
+        Parity::Odd => cmp::max(nl - 1, 1),
+    }
+}
+
+// Equivalent to floor(value / 2^17 + 0.5) for a fixed-point value with 17 fractional bits.
+fn round_index(value: i64) -> i64 {
+    (value + CPR_MAX / 2).div_euclid(CPR_MAX)
+}
+
+// Scales a fixed-point zone index plus fraction to degrees.
+fn to_degrees(zone_size: f64, value: i64) -> f64 {
+    zone_size * (value as f64 / CPR_SCALE)
+}
+
+// Computes the latitudes of both frames of a pair, with each latitude kept in the range [0, zone)
+// of the first zone for surface positions or wrapped to [-90, 270) for airborne positions.
+fn global_latitudes(zone: f64, lat_even: i64, lat_odd: i64) -> (f64, f64) {
+    let j = round_index(59 * lat_even - 60 * lat_odd);
+    let rlat_even = to_degrees(zone / 60.0, j.rem_euclid(60) * CPR_MAX + lat_even);
+    let rlat_odd = to_degrees(zone / 59.0, j.rem_euclid(59) * CPR_MAX + lat_odd);
+    (rlat_even, rlat_odd)
+}
+
+// Picks the hemisphere closest to the reference latitude for a surface latitude decoded in the
+// first zone. As -90, 0 and 90 all encode to 0, a latitude of 0 is moved to the pole closest to the
+// reference when the reference is more than 45 degrees from the equator.
+fn closest_hemisphere(rlat: f64, reference_latitude: f64) -> f64 {
+    if rlat == 0.0 {
+        if reference_latitude < -45.0 {
+            -90.0
+        } else if reference_latitude > 45.0 {
+            90.0
+        } else {
+            0.0
+        }
+    } else if rlat - reference_latitude > 45.0 {
+        rlat - 90.0
+    } else {
+        rlat
+    }
+}
+
+fn global_longitude(zone: f64, rlat: f64, lon_even: i64, lon_odd: i64, parity: &Parity) -> f64 {
+    let nl = cpr_nl(rlat) as i64;
+    let ni = cpr_n(rlat, parity);
+    let m = round_index(lon_even * (nl - 1) - lon_odd * nl);
+    let lon = match parity {
+        Parity::Even => lon_even,
+        Parity::Odd => lon_odd,
+    };
+    (zone / ni as f64) * (m.rem_euclid(ni) as f64 + lon as f64 / CPR_SCALE)
+}
+
+/// Calculates a globally unambiguous position based on a pair of airborne frames. Takes the same
+/// arguments as [`crate::cpr::get_position`].
+pub fn get_position(
+    cpr_frames: (&CPRFrame, &CPRFrame),
+    receive_times: Option<&ReceiveTimes>,
+) -> Result<Position, CPRError> {
+    let latest_frame = cpr_frames.1;
+    let (even_frame, odd_frame) = split_parity(cpr_frames)?;
+    check_encodings(cpr_frames, false)?;
+    if let Some(receive_times) = receive_times {
+        receive_times.check(false)?;
+    }
+
+    let (lat_even, lon_even) = cpr_coordinates(even_frame);
+    let (lat_odd, lon_odd) = cpr_coordinates(odd_frame);
+
+    let (mut rlat_even, mut rlat_odd) = global_latitudes(360.0, lat_even, lat_odd);
+    if rlat_even >= 270.0 {
+        rlat_even -= 360.0;
+    }
+    if rlat_odd >= 270.0 {
+        rlat_odd -= 360.0;
+    }
+    check_latitudes(rlat_even, rlat_odd)?;
+
+    let rlat = match latest_frame.parity {
+        Parity::Even => rlat_even,
+        Parity::Odd => rlat_odd,
+    };
+    let mut rlon = global_longitude(360.0, rlat, lon_even, lon_odd, &latest_frame.parity);
+    rlon -= ((rlon + 180.0) / 360.0).floor() * 360.0;
+
+    Ok(Position {
+        latitude: rlat,
+        longitude: rlon,
+    })
+}
+
+/// Calculates the position of an aircraft on the surface based on a pair of surface frames. Takes
+/// the same arguments as [`crate::cpr::get_surface_position`].
+pub fn get_surface_position(
+    cpr_frames: (&CPRFrame, &CPRFrame),
+    reference: &Position,
+    receive_times: Option<&ReceiveTimes>,
+) -> Result<Position, CPRError> {
+    let latest_frame = cpr_frames.1;
+    let (even_frame, odd_frame) = split_parity(cpr_frames)?;
+    check_encodings(cpr_frames, true)?;
+    if let Some(receive_times) = receive_times {
+        receive_times.check(true)?;
+    }
+
+    let (lat_even, lon_even) = cpr_coordinates(even_frame);
+    let (lat_odd, lon_odd) = cpr_coordinates(odd_frame);
+
+    let (rlat_even, rlat_odd) = global_latitudes(90.0, lat_even, lat_odd);
+    let rlat_even = closest_hemisphere(rlat_even, reference.latitude);
+    let rlat_odd = closest_hemisphere(rlat_odd, reference.latitude);
+    check_latitudes(rlat_even, rlat_odd)?;
+
+    let rlat = match latest_frame.parity {
+        Parity::Even => rlat_even,
+        Parity::Odd => rlat_odd,
+    };
+    // Pick the quadrant closest to the reference position
+    let mut rlon = global_longitude(90.0, rlat, lon_even, lon_odd, &latest_frame.parity);
+    rlon += ((reference.longitude - rlon + 45.0) / 90.0).floor() * 90.0;
+    rlon -= ((rlon + 180.0) / 360.0).floor() * 360.0;
+
+    Ok(Position {
+        latitude: rlat,
+        longitude: rlon,
+    })
+}
+
+// Only the zone index of the reference position depends on floating point arithmetic, as the
+// reference is not quantised. Positions more than half a zone away from the reference are rejected.
+fn get_local_position_in_zone(
+    cpr_frame: &CPRFrame,
+    reference: &Position,
+    zone: f64,
+) -> Option<Position> {
+    let (lat, lon) = cpr_coordinates(cpr_frame);
+    let fractional_lat = lat as f64 / CPR_SCALE;
+    let fractional_lon = lon as f64 / CPR_SCALE;
+
+    let d_lat = match cpr_frame.parity {
+        Parity::Even => zone / 60.0,
+        Parity::Odd => zone / 59.0,
+    };
+    let j = (reference.latitude / d_lat).floor() as i64
+        + (0.5 + reference.latitude.rem_euclid(d_lat) / d_lat - fractional_lat).floor() as i64;
+    let rlat = to_degrees(d_lat, j * CPR_MAX + lat);
+    if !(-90.0..=90.0).contains(&rlat) || (rlat - reference.latitude).abs() > d_lat / 2.0 {
+        return None;
+    }
+
+    let d_lon = zone / cpr_n(rlat, &cpr_frame.parity) as f64;
+    let m = (reference.longitude / d_lon).floor() as i64
+        + (0.5 + reference.longitude.rem_euclid(d_lon) / d_lon - fractional_lon).floor() as i64;
+    let mut rlon = to_degrees(d_lon, m * CPR_MAX + lon);
+    if rlon > 180.0 {
+        rlon -= 360.0;
+    }
+    if (rlon - reference.longitude).abs() > d_lon / 2.0 {
+        return None;
+    }
+
+    Some(Position {
+        latitude: rlat,
+        longitude: rlon,
+    })
+}
+
+/// Calculates the position of an airborne aircraft based on a single frame and a reference position.
+/// Takes the same arguments as [`crate::cpr::get_local_position`]. Unlike it, longitudes are not
+/// wrapped around the antimeridian, so as in readsb positions on the other side of the antimeridian
+/// from the reference are rejected.
+pub fn get_local_position(cpr_frame: &CPRFrame, reference: &Position) -> Option<Position> {
+    if is_surface(cpr_frame) {
+        return None;
+    }
+    get_local_position_in_zone(cpr_frame, reference, 360.0)
+}
+
+/// Calculates the position of an aircraft on the surface based on a single frame and a reference
+/// position. Takes the same arguments as [`crate::cpr::get_surface_local_position`]. Unlike it,
+/// longitudes are not wrapped around the antimeridian, so as in readsb positions on the other side of
+/// the antimeridian from the reference are rejected.
+pub fn get_surface_local_position(cpr_frame: &CPRFrame, reference: &Position) -> Option<Position> {
+    if !is_surface(cpr_frame) {
+        return None;
+    }
+    get_local_position_in_zone(cpr_frame, reference, 90.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CPREncoding, CPRPosition};
+
+    // Double precision decoders transcribed from readsb's cpr.c, used as a reference to check the
+    // fixed-point decoders produce exactly the same output.
+    mod readsb {
+        use crate::cpr::cpr_nl;
+
+        fn cpr_mod_int(a: i32, b: i32) -> i32 {
+            let res = a % b;
+            if res < 0 {
+                res + b
+            } else {
+                res
+            }
+        }
+
+        fn cpr_mod_double(a: f64, b: f64) -> f64 {
+            let res = a % b;
+            if res < 0.0 {
+                res + b
+            } else {
+                res
+            }
+        }
+
+        fn cpr_n_function(lat: f64, fflag: bool) -> i32 {
+            let nl = cpr_nl(lat) as i32 - if fflag { 1 } else { 0 };
+            if nl < 1 {
+                1
+            } else {
+                nl
+            }
+        }
+
+        fn cpr_dlon_function(lat: f64, fflag: bool, surface: bool) -> f64 {
+            (if surface { 90.0 } else { 360.0 }) / cpr_n_function(lat, fflag) as f64
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        fn decode_cpr_global(
+            surface: bool,
+            reflat: f64,
+            reflon: f64,
+            even_cprlat: i32,
+            even_cprlon: i32,
+            odd_cprlat: i32,
+            odd_cprlon: i32,
+            fflag: bool,
+        ) -> Option<(f64, f64)> {
+            let zone = if surface { 90.0 } else { 360.0 };
+            let air_dlat0 = zone / 60.0;
+            let air_dlat1 = zone / 59.0;
+            let lat0 = even_cprlat as f64;
+            let lat1 = odd_cprlat as f64;
+            let lon0 = even_cprlon as f64;
+            let lon1 = odd_cprlon as f64;
+
+            let j = (((59.0 * lat0 - 60.0 * lat1) / 131072.0) + 0.5).floor() as i32;
+            let mut rlat0 = air_dlat0 * (cpr_mod_int(j, 60) as f64 + lat0 / 131072.0);
+            let mut rlat1 = air_dlat1 * (cpr_mod_int(j, 59) as f64 + lat1 / 131072.0);
+
+            if surface {
+                // -90, 0 and +90 all encode to zero
+                if rlat0 == 0.0 {
+                    if reflat < -45.0 {
+                        rlat0 = -90.0;
+                    } else if reflat > 45.0 {
+                        rlat0 = 90.0;
+                    }
+                } else if rlat0 - reflat > 45.0 {
+                    rlat0 -= 90.0;
+                }
+                if rlat1 == 0.0 {
+                    if reflat < -45.0 {
+                        rlat1 = -90.0;
+                    } else if reflat > 45.0 {
+                        rlat1 = 90.0;
+                    }
+                } else if rlat1 - reflat > 45.0 {
+                    rlat1 -= 90.0;
+                }
+            } else {
+                if rlat0 >= 270.0 {
+                    rlat0 -= 360.0;
+                }
+                if rlat1 >= 270.0 {
+                    rlat1 -= 360.0;
+                }
+            }
+
+            if !(-90.0..=90.0).contains(&rlat0) || !(-90.0..=90.0).contains(&rlat1) {
+                return None;
+            }
+            if cpr_nl(rlat0) != cpr_nl(rlat1) {
+                return None;
+            }
+
+            let (rlat, mut rlon) = if fflag {
+                let ni = cpr_n_function(rlat1, true);
+                let nl = cpr_nl(rlat1) as f64;
+                let m = ((((lon0 * (nl - 1.0)) - (lon1 * nl)) / 131072.0) + 0.5).floor() as i32;
+                let rlon = cpr_dlon_function(rlat1, true, surface)
+                    * (cpr_mod_int(m, ni) as f64 + lon1 / 131072.0);
+                (rlat1, rlon)
+            } else {
+                let ni = cpr_n_function(rlat0, false);
+                let nl = cpr_nl(rlat0) as f64;
+                let m = ((((lon0 * (nl - 1.0)) - (lon1 * nl)) / 131072.0) + 0.5).floor() as i32;
+                let rlon = cpr_dlon_function(rlat0, false, surface)
+                    * (cpr_mod_int(m, ni) as f64 + lon0 / 131072.0);
+                (rlat0, rlon)
+            };
+
+            if surface {
+                rlon += ((reflon - rlon + 45.0) / 90.0).floor() * 90.0;
+            }
+            rlon -= ((rlon + 180.0) / 360.0).floor() * 360.0;
+
+            Some((rlat, rlon))
+        }
+
+        pub(super) fn decode_cpr_airborne(
+            even: (i32, i32),
+            odd: (i32, i32),
+            fflag: bool,
+        ) -> Option<(f64, f64)> {
+            decode_cpr_global(false, 0.0, 0.0, even.0, even.1, odd.0, odd.1, fflag)
+        }
+
+        pub(super) fn decode_cpr_surface(
+            reference: (f64, f64),
+            even: (i32, i32),
+            odd: (i32, i32),
+            fflag: bool,
+        ) -> Option<(f64, f64)> {
+            decode_cpr_global(
+                true,
+                reference.0,
+                reference.1,
+                even.0,
+                even.1,
+                odd.0,
+                odd.1,
+                fflag,
+            )
+        }
+
+        pub(super) fn decode_cpr_relative(
+            reference: (f64, f64),
+            cpr: (i32, i32),
+            fflag: bool,
+            surface: bool,
+        ) -> Option<(f64, f64)> {
+            let (reflat, reflon) = reference;
+            let fractional_lat = cpr.0 as f64 / 131072.0;
+            let fractional_lon = cpr.1 as f64 / 131072.0;
+
+            let air_dlat = (if surface { 90.0 } else { 360.0 }) / (if fflag { 59.0 } else { 60.0 });
+
+            let j = ((reflat / air_dlat).floor()
+                + (0.5 + cpr_mod_double(reflat, air_dlat) / air_dlat - fractional_lat).floor())
+                as i32;
+            let rlat = air_dlat * (j as f64 + fractional_lat);
+            if !(-90.0..=90.0).contains(&rlat) || (rlat - reflat).abs() > air_dlat / 2.0 {
+                return None;
+            }
+
+            let air_dlon = cpr_dlon_function(rlat, fflag, surface);
+            let m = ((reflon / air_dlon).floor()
+                + (0.5 + cpr_mod_double(reflon, air_dlon) / air_dlon - fractional_lon).floor())
+                as i32;
+            let mut rlon = air_dlon * (m as f64 + fractional_lon);
+            if rlon > 180.0 {
+                rlon -= 360.0;
+            }
+            if (rlon - reflon).abs() > air_dlon / 2.0 {
+                return None;
+            }
+
+            Some((rlat, rlon))
+        }
+    }
+
+    fn frame(lat: i32, lon: i32, parity: Parity, encoding: CPREncoding) -> CPRFrame {
+        CPRFrame {
+            position: CPRPosition {
+                latitude: lat as u32,
+                longitude: lon as u32,
+            },
+            parity,
+            encoding,
+        }
+    }
+
+    // Encodes a position in CPR format, used to generate frames close to zone boundaries.
+    fn encode(lat: f64, lon: f64, parity: &Parity, zone: f64) -> (i32, i32) {
+        let i = match parity {
+            Parity::Even => 0.0,
+            Parity::Odd => 1.0,
+        };
+        let d_lat = zone / (60.0 - i);
+        let yz = (CPR_SCALE * lat.rem_euclid(d_lat) / d_lat + 0.5).floor();
+        let rlat = d_lat * (yz / CPR_SCALE + (lat / d_lat).floor());
+        let d_lon = zone / cpr_n(rlat, parity) as f64;
+        let xz = (CPR_SCALE * lon.rem_euclid(d_lon) / d_lon + 0.5).floor();
+        (
+            (yz as i64).rem_euclid(CPR_MAX) as i32,
+            (xz as i64).rem_euclid(CPR_MAX) as i32,
+        )
+    }
+
+    fn same(result: Option<Position>, expected: Option<(f64, f64)>) -> bool {
+        match (result, expected) {
+            (Some(p), Some((lat, lon))) => {
+                p.latitude.to_bits() == lat.to_bits() && p.longitude.to_bits() == lon.to_bits()
+            }
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    // Latitudes at which the number of longitude zones changes, plus the poles, equator and the
+    // latitude zone boundaries around them.
+    fn boundary_latitudes() -> Vec<f64> {
+        let mut latitudes: Vec<f64> = (2..=59)
+            .map(|nl| {
+                use std::f64::consts::PI;
+                let nz = 15.0;
+                (180.0 / PI)
+                    * (((1.0 - (PI / (2.0 * nz)).cos()) / (1.0 - ((2.0 * PI) / (nl as f64)).cos()))
+                        .sqrt())
+                    .acos()
+            })
+            .collect();
+        latitudes.extend([0.0, 87.0, 89.9, 6.0, 360.0 / 59.0]);
+        latitudes
+            .iter()
+            .flat_map(|lat| [*lat, -*lat])
+            .flat_map(|lat| [lat - 0.001, lat, lat + 0.001])
+            .collect()
+    }
+
+    const LONGITUDES: [f64; 7] = [-180.0, -179.999, -90.0, -0.001, 0.0, 89.999, 179.999];
+
+    #[test]
+    fn airborne_global_matches_readsb_near_boundaries() {
+        for lat in boundary_latitudes() {
+            for lon in LONGITUDES {
+                let even = encode(lat, lon, &Parity::Even, 360.0);
+                let odd = encode(lat, lon, &Parity::Odd, 360.0);
+                for delta in -3..=3 {
+                    let even = ((even.0 + delta).rem_euclid(1 << 17), even.1);
+                    let even_frame = frame(even.0, even.1, Parity::Even, CPREncoding::Airborne);
+                    let odd_frame = frame(odd.0, odd.1, Parity::Odd, CPREncoding::Airborne);
+                    assert!(same(
+                        get_position((&odd_frame, &even_frame), None).ok(),
+                        readsb::decode_cpr_airborne(even, odd, false)
+                    ));
+                    assert!(same(
+                        get_position((&even_frame, &odd_frame), None).ok(),
+                        readsb::decode_cpr_airborne(even, odd, true)
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn airborne_global_matches_readsb_full_input_space() {
+        // Sweep every encoded latitude and longitude of the even frame against fixed odd frames
+        let odd_frames = [(0, 0), (74158, 50194), (131071, 131071), (65536, 1)];
+        for odd in odd_frames {
+            let odd_frame = frame(odd.0, odd.1, Parity::Odd, CPREncoding::Airborne);
+            for value in 0..(1 << 17) {
+                for even in [(value, odd.1), (odd.0, value)] {
+                    let even_frame = frame(even.0, even.1, Parity::Even, CPREncoding::Airborne);
+                    assert!(same(
+                        get_position((&odd_frame, &even_frame), None).ok(),
+                        readsb::decode_cpr_airborne(even, odd, false)
+                    ));
+                    assert!(same(
+                        get_position((&even_frame, &odd_frame), None).ok(),
+                        readsb::decode_cpr_airborne(even, odd, true)
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn surface_global_matches_readsb_near_boundaries() {
+        for lat in boundary_latitudes() {
+            for lon in LONGITUDES {
+                let reference = Position {
+                    latitude: (lat + 0.3).clamp(-90.0, 90.0),
+                    longitude: lon - 0.3,
+                };
+                let even = encode(lat, lon, &Parity::Even, 90.0);
+                let odd = encode(lat, lon, &Parity::Odd, 90.0);
+                for delta in -3..=3 {
+                    let odd = (odd.0, (odd.1 + delta).rem_euclid(1 << 17));
+                    let even_frame = frame(even.0, even.1, Parity::Even, CPREncoding::Surface);
+                    let odd_frame = frame(odd.0, odd.1, Parity::Odd, CPREncoding::Surface);
+                    let r = (reference.latitude, reference.longitude);
+                    assert!(same(
+                        get_surface_position((&odd_frame, &even_frame), &reference, None).ok(),
+                        readsb::decode_cpr_surface(r, even, odd, false)
+                    ));
+                    assert!(same(
+                        get_surface_position((&even_frame, &odd_frame), &reference, None).ok(),
+                        readsb::decode_cpr_surface(r, even, odd, true)
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn surface_global_matches_readsb_at_poles() {
+        // A latitude of 0 decodes to the pole when the reference is closer to it
+        let even_frame = frame(0, 0, Parity::Even, CPREncoding::Surface);
+        let odd_frame = frame(0, 0, Parity::Odd, CPREncoding::Surface);
+        for (reference_latitude, latitude) in [(80.0, 90.0), (-80.0, -90.0), (30.0, 0.0)] {
+            let reference = Position {
+                latitude: reference_latitude,
+                longitude: 10.0,
+            };
+            let r = (reference.latitude, reference.longitude);
+            let position =
+                get_surface_position((&odd_frame, &even_frame), &reference, None).unwrap();
+            assert_eq!(position.latitude, latitude);
+            assert!(same(
+                Some(position),
+                readsb::decode_cpr_surface(r, (0, 0), (0, 0), false)
+            ));
+            assert!(same(
+                get_surface_position((&even_frame, &odd_frame), &reference, None).ok(),
+                readsb::decode_cpr_surface(r, (0, 0), (0, 0), true)
+            ));
+        }
+    }
+
+    #[test]
+    fn local_matches_readsb_full_input_space() {
+        // Sweep every encoded latitude and longitude against references in zones with a different
+        // number of longitude zones, at the equator, poles and antimeridian
+        let references = [
+            (0.0, 0.0),
+            (52.258, 3.918),
+            (-33.9, -70.8),
+            (29.911, -0.001),
+            (87.5, 179.999),
+            (-89.9, -179.999),
+        ];
+        for (latitude, longitude) in references {
+            let reference = Position {
+                latitude,
+                longitude,
+            };
+            let r = (latitude, longitude);
+            for value in 0..(1 << 17) {
+                for cpr in [(value, 65536), (65536, value)] {
+                    for parity in [Parity::Even, Parity::Odd] {
+                        let odd = parity == Parity::Odd;
+                        let cpr_frame = frame(cpr.0, cpr.1, parity.clone(), CPREncoding::Airborne);
+                        assert!(same(
+                            get_local_position(&cpr_frame, &reference),
+                            readsb::decode_cpr_relative(r, cpr, odd, false)
+                        ));
+                        let cpr_frame = frame(cpr.0, cpr.1, parity, CPREncoding::Surface);
+                        assert!(same(
+                            get_surface_local_position(&cpr_frame, &reference),
+                            readsb::decode_cpr_relative(r, cpr, odd, true)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn decode_known_positions() {
+        let even = frame(93000, 51372, Parity::Even, CPREncoding::Airborne);
+        let odd = frame(74158, 50194, Parity::Odd, CPREncoding::Airborne);
+        let position = get_position((&odd, &even), None).unwrap();
+        assert_eq!(position.latitude, 52.2572021484375);
+        assert_eq!(position.longitude, 3.91937255859375);
+
+        let reference = Position {
+            latitude: 52.258,
+            longitude: 3.918,
+        };
+        let position = get_local_position(&even, &reference).unwrap();
+        assert_eq!(position.latitude, 52.2572021484375);
+        assert_eq!(position.longitude, 3.91937255859375);
+    }
+
+    #[test]
+    fn local_position_across_antimeridian_rejected() {
+        // Encoded at latitude 0 and longitude -179.95, which the parent decoder wraps
+        let cpr_frame = frame(0, 66610, Parity::Even, CPREncoding::Airborne);
+        let reference = Position {
+            latitude: 0.0,
+            longitude: 179.95,
+        };
+        assert_eq!(get_local_position(&cpr_frame, &reference), None);
+        assert!(crate::cpr::get_local_position(&cpr_frame, &reference).is_some());
+    }
+}
@@ -1,11 +1,23 @@
 //! Parse ADS-B/Mode-S messages. Messages with the following Downlink Formats (DF) are supported:
 //!
+//! - **DF 0**: Short air-air surveillance (ACAS)
+//! - **DF 4**: Surveillance altitude reply
 //! - **DF 5**: Surveillance identity (squawk code)
+//! - **DF 11**: All-call reply
+//! - **DF 16**: Long air-air surveillance (ACAS)
 //! - **DF 17/18**: Automatic Dependent Surveillance - Broadcast (ADS-B)
 //!   - **TC 1-4**: Aircraft identification and category
-//!   - **TC 9-18**: Airborne position
+//!   - **TC 5-8**: Surface position
+//!   - **TC 9-18**: Airborne position with barometric altitude
+//!   - **TC 20-22**: Airborne position with GNSS height
 //!   - **TC 19**: Airborne velocity
+//!   - **TC 28**: Aircraft status (emergency/priority status and ACAS RA broadcast)
+//! - **DF 20**: Comm-B altitude reply
+//! - **DF 21**: Comm-B identity reply
+//!
+//! The MB field of Comm-B replies can be decoded using the [`bds`] module.
 
+pub mod bds;
 pub mod cpr;
 mod crc;
 mod parser;
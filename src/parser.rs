@@ -1,3 +1,4 @@
+use super::bds::decode_resolution_advisory;
 use super::crc::*;
 use super::types::*;
 use nom::bits::bits;
@@ -5,7 +6,7 @@ use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while_m_n};
 use nom::combinator::{map, map_res, peek, verify};
 use nom::error::{make_error, ErrorKind};
-use nom::multi::{count, many0};
+use nom::multi::{count, fill, many0};
 use nom::Err;
 use nom::IResult;
 use nom::Parser;
@@ -14,7 +15,7 @@ use std::f64::consts::PI;
 
 const CHAR_LOOKUP: &[u8; 64] = b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
 
-fn decode_callsign(encoded: Vec<u8>) -> String {
+pub(crate) fn decode_callsign(encoded: Vec<u8>) -> String {
     encoded
         .into_iter()
         .map(|b| CHAR_LOOKUP[b as usize] as char)
@@ -37,22 +38,49 @@ fn parse_aircraft_identification(
     Ok((input, message))
 }
 
-fn parse_altitude(input: (&[u8], usize)) -> IResult<(&[u8], usize), u16> {
-    let (input, (l, q, r)): (_, (u16, u16, u16)) = (
-        take_bits(7u8),
-        alt((
-            map(tag_bits(0b0, 1u8), |_| 100),
-            map(tag_bits(0b1, 1u8), |_| 25),
-        )),
-        take_bits(4u8),
-    )
-        .parse(input)?;
-    let altitude = (l.rotate_left(4) + r)
-        .checked_mul(q)
-        .and_then(|r| r.checked_sub(1000));
-    match altitude {
-        Some(value) => Ok((input, value)),
-        None => Err(Err::Error(make_error(input, ErrorKind::TooLarge))),
+// Decoded altitude, `Ok(None)` if not available
+type AltitudeField = Result<Option<Altitude>, AltitudeError>;
+
+// The altitude field of airborne position messages is the AC13 field without the M bit
+fn decode_altitude_field(ac: u16) -> AltitudeField {
+    decode_ac13_field(((ac & 0x0FC0) << 1) | (ac & 0x003F))
+}
+
+fn parse_altitude(input: (&[u8], usize)) -> IResult<(&[u8], usize), AltitudeField> {
+    map(take_bits(12u8), decode_altitude_field).parse(input)
+}
+
+// GNSS height is encoded in the same way as the barometric altitude, as decoded by readsb, but
+// only 25 ft increments (Q bit set) carry the resolution expected from a GNSS source
+fn parse_gnss_height(input: (&[u8], usize)) -> IResult<(&[u8], usize), AltitudeField> {
+    map(take_bits(12u8), |ac: u16| {
+        if ac != 0 && ac & 0x0010 == 0 {
+            return Err(AltitudeError::GillhamCodedGnssHeight);
+        }
+        decode_altitude_field(ac).map(|altitude| {
+            altitude.map(|altitude| Altitude {
+                value: altitude.value,
+                source: AltitudeSource::Gnss,
+            })
+        })
+    })
+    .parse(input)
+}
+
+// Upper bound of the horizontal containment radius for each type code, which depends on the NIC
+// supplements for type codes 11, 13 and 16 (DO-260B Table 2-14)
+fn containment_radius(type_code: u8) -> Option<f64> {
+    match type_code {
+        9 | 20 => Some(7.5),
+        10 | 21 => Some(25.0),
+        11 => Some(185.2),
+        12 => Some(370.4),
+        13 => Some(1111.2),
+        14 => Some(1852.0),
+        15 => Some(3704.0),
+        16 => Some(14816.0),
+        17 => Some(37040.0),
+        _ => None,
     }
 }
 
@@ -68,26 +96,107 @@ fn parse_coordinate(input: (&[u8], usize)) -> IResult<(&[u8], usize), u32> {
     take_bits(17u32)(input)
 }
 
+fn parse_surveillance_status(input: (&[u8], usize)) -> IResult<(&[u8], usize), SurveillanceStatus> {
+    map(take_bits(2u8), |status: u8| match status {
+        0 => SurveillanceStatus::NoCondition,
+        1 => SurveillanceStatus::PermanentAlert,
+        2 => SurveillanceStatus::TemporaryAlert,
+        _ => SurveillanceStatus::SPICondition,
+    })
+    .parse(input)
+}
+
 fn parse_airborne_position(input: (&[u8], usize)) -> IResult<(&[u8], usize), ADSBMessageKind> {
-    let (input, _): (_, (u8, u8)) = (
-        verify(take_bits(5u8), |tc| *tc >= 9 && *tc <= 18),
-        take_bits(3u8),
+    let (input, (type_code, surveillance_status, nic_supplement_b)): (_, (u8, _, u8)) = (
+        verify(take_bits(5u8), |tc| {
+            (9..=18).contains(tc) || (20..=22).contains(tc)
+        }),
+        parse_surveillance_status,
+        take_bits(1u8),
     )
         .parse(input)?;
 
-    let (input, (altitude, _)): (_, (u16, u8)) = (parse_altitude, take_bits(1u8)).parse(input)?;
+    let (input, (altitude, utc_synchronized)): (_, (_, u8)) = if type_code >= 20 {
+        (parse_gnss_height, take_bits(1u8)).parse(input)?
+    } else {
+        (parse_altitude, take_bits(1u8)).parse(input)?
+    };
     let (input, cpr_parity) = parse_cpr_parity(input)?;
     let (input, (cpr_latitude, cpr_longitude)) =
         (parse_coordinate, parse_coordinate).parse(input)?;
 
     let message = ADSBMessageKind::AirbornePosition {
         altitude,
+        surveillance_status,
+        nic_supplement_b: nic_supplement_b == 1,
+        utc_synchronized: utc_synchronized == 1,
+        containment_radius: containment_radius(type_code),
+        cpr_frame: CPRFrame {
+            parity: cpr_parity,
+            position: CPRPosition {
+                latitude: cpr_latitude,
+                longitude: cpr_longitude,
+            },
+            encoding: CPREncoding::Airborne,
+        },
+    };
+    Ok((input, message))
+}
+
+// Ground speed is encoded in the movement field using the following quantisation levels:
+//
+// 0: no information available
+// 1: stopped (v < 0.125 kt)
+// 2-8: 0.125 kt <= v < 1 kt in 0.125 kt steps
+// 9-12: 1 kt <= v < 2 kt in 0.25 kt steps
+// 13-38: 2 kt <= v < 15 kt in 0.5 kt steps
+// 39-93: 15 kt <= v < 70 kt in 1 kt steps
+// 94-108: 70 kt <= v < 100 kt in 2 kt steps
+// 109-123: 100 kt <= v < 175 kt in 5 kt steps
+// 124: v >= 175 kt
+// 125-127: reserved
+
+fn decode_movement(m: u8) -> Movement {
+    use Movement::*;
+    match m {
+        0 => Unavailable,
+        1 => Stopped,
+        2..=8 => GroundSpeed(0.125 + f64::from(m - 2) * 0.125),
+        9..=12 => GroundSpeed(1.0 + f64::from(m - 9) * 0.25),
+        13..=38 => GroundSpeed(2.0 + f64::from(m - 13) * 0.5),
+        39..=93 => GroundSpeed(15.0 + f64::from(m - 39)),
+        94..=108 => GroundSpeed(70.0 + f64::from(m - 94) * 2.0),
+        109..=123 => GroundSpeed(100.0 + f64::from(m - 109) * 5.0),
+        124 => AtLeast175Knots,
+        _ => Reserved(m),
+    }
+}
+
+fn parse_surface_position(input: (&[u8], usize)) -> IResult<(&[u8], usize), ADSBMessageKind> {
+    let (input, (_, movement, track_status, track, utc_synchronized)): (_, (u8, u8, u8, u8, u8)) =
+        (
+            verify(take_bits(5u8), |tc| *tc >= 5 && *tc <= 8),
+            take_bits(7u8),
+            take_bits(1u8),
+            take_bits(7u8),
+            take_bits(1u8),
+        )
+            .parse(input)?;
+    let (input, cpr_parity) = parse_cpr_parity(input)?;
+    let (input, (cpr_latitude, cpr_longitude)) =
+        (parse_coordinate, parse_coordinate).parse(input)?;
+
+    let message = ADSBMessageKind::SurfacePosition {
+        movement: decode_movement(movement),
+        ground_track: (track_status == 1).then(|| f64::from(track) * 360.0 / 128.0),
+        utc_synchronized: utc_synchronized == 1,
         cpr_frame: CPRFrame {
             parity: cpr_parity,
-            position: Position {
-                latitude: cpr_latitude.into(),
-                longitude: cpr_longitude.into(),
+            position: CPRPosition {
+                latitude: cpr_latitude,
+                longitude: cpr_longitude,
             },
+            encoding: CPREncoding::Surface,
         },
     };
     Ok((input, message))
@@ -112,6 +221,11 @@ fn parse_sign(input: (&[u8], usize)) -> IResult<(&[u8], usize), i16> {
     .parse(input)
 }
 
+// Velocity fields use 0 to indicate no information is available and their maximum value to
+// indicate the speed exceeds the largest value that can be encoded
+const MAX_VELOCITY: u16 = 1023;
+const MAX_VERTICAL_RATE: u16 = 511;
+
 fn parse_velocity(input: (&[u8], usize)) -> IResult<(&[u8], usize), u16> {
     take_bits(10u16)(input)
 }
@@ -120,41 +234,153 @@ fn parse_vertical_rate(input: (&[u8], usize)) -> IResult<(&[u8], usize), u16> {
     take_bits(9u16)(input)
 }
 
+// Ground speed is encoded as signed east-west and north-south velocity components, with speeds
+// multiplied by 4 for supersonic aircraft
+fn parse_ground_velocity(
+    input: (&[u8], usize),
+    supersonic: bool,
+) -> IResult<(&[u8], usize), Velocity> {
+    let (input, (ew_sign, ew_vel)): (_, (i16, u16)) = (parse_sign, parse_velocity).parse(input)?;
+    let (input, (ns_sign, ns_vel)): (_, (i16, u16)) = (parse_sign, parse_velocity).parse(input)?;
+
+    let multiplier = if supersonic { 4 } else { 1 };
+    let east_west = (ew_vel != 0).then(|| (ew_vel as i16 - 1) * ew_sign * multiplier);
+    let north_south = (ns_vel != 0).then(|| (ns_vel as i16 - 1) * ns_sign * multiplier);
+
+    let (heading, ground_speed) = match (east_west, north_south) {
+        (Some(v_ew), Some(v_ns)) => {
+            let (v_ew, v_ns) = (f64::from(v_ew), f64::from(v_ns));
+            let h = v_ew.atan2(v_ns) * (360.0 / (2.0 * PI));
+            let heading = if h < 0.0 { h + 360.0 } else { h };
+            (Some(heading), Some((v_ew.powi(2) + v_ns.powi(2)).sqrt()))
+        }
+        _ => (None, None),
+    };
+
+    let velocity = Velocity::GroundSpeed {
+        east_west,
+        north_south,
+        heading,
+        ground_speed,
+        saturated: ew_vel == MAX_VELOCITY || ns_vel == MAX_VELOCITY,
+    };
+    Ok((input, velocity))
+}
+
+fn parse_airspeed_type(input: (&[u8], usize)) -> IResult<(&[u8], usize), AirspeedType> {
+    alt((
+        map(tag_bits(0b0, 1u8), |_| AirspeedType::Indicated),
+        map(tag_bits(0b1, 1u8), |_| AirspeedType::True),
+    ))
+    .parse(input)
+}
+
+// Airspeed is encoded along with the magnetic heading, each preceded by a status bit for the
+// heading and the type of airspeed respectively
+fn parse_airspeed_velocity(
+    input: (&[u8], usize),
+    supersonic: bool,
+) -> IResult<(&[u8], usize), Velocity> {
+    let (input, (heading_status, heading, airspeed_type, airspeed)): (_, (u8, u16, _, u16)) = (
+        take_bits(1u8),
+        take_bits(10u16),
+        parse_airspeed_type,
+        parse_velocity,
+    )
+        .parse(input)?;
+
+    let multiplier = if supersonic { 4 } else { 1 };
+    let velocity = Velocity::Airspeed {
+        heading: (heading_status == 1).then(|| f64::from(heading) * 360.0 / 1024.0),
+        airspeed: airspeed.checked_sub(1).map(|v| v * multiplier),
+        airspeed_type,
+        saturated: airspeed == MAX_VELOCITY,
+    };
+    Ok((input, velocity))
+}
+
 fn parse_airborne_velocity(input: (&[u8], usize)) -> IResult<(&[u8], usize), ADSBMessageKind> {
-    let (input, _): (_, (u8, u8, u8)) = (
+    let (input, (_, subtype, intent_change, ifr_capability, navigation_accuracy)): (
+        _,
+        (u8, u8, u8, u8, u8),
+    ) = (
         verify(take_bits(5u8), |tc| *tc == 19),
-        verify(take_bits(3u8), |st| *st == 1),
-        take_bits(5u8),
+        verify(take_bits(3u8), |st| (1..=4).contains(st)),
+        take_bits(1u8),
+        take_bits(1u8),
+        take_bits(3u8),
     )
         .parse(input)?;
 
-    let (input, (ew_sign, ew_vel)): (_, (i16, u16)) = (parse_sign, parse_velocity).parse(input)?;
-    let (input, (ns_sign, ns_vel)): (_, (i16, u16)) = (parse_sign, parse_velocity).parse(input)?;
-    let (input, (vrate_src, vrate_sign, vrate_value, _)): (_, (VerticalRateSource, i16, u16, u16)) =
-        (
-            parse_vertical_rate_source,
-            parse_sign,
-            parse_vertical_rate,
-            take_bits(10u16),
-        )
-            .parse(input)?;
-
-    let v_ew = ((ew_vel as i16 - 1) * ew_sign) as f64;
-    let v_ns = ((ns_vel as i16 - 1) * ns_sign) as f64;
-    let h = v_ew.atan2(v_ns) * (360.0 / (2.0 * PI));
-    let heading = if h < 0.0 { h + 360.0 } else { h };
+    let supersonic = subtype == 2 || subtype == 4;
+    let (input, velocity) = if subtype <= 2 {
+        parse_ground_velocity(input, supersonic)?
+    } else {
+        parse_airspeed_velocity(input, supersonic)?
+    };
+    let (input, (vrate_src, vrate_sign, vrate_value)): (_, (VerticalRateSource, i16, u16)) =
+        (parse_vertical_rate_source, parse_sign, parse_vertical_rate).parse(input)?;
+    let (input, (_, diff_sign, diff_value)): (_, (u8, i16, i16)) =
+        (take_bits(2u8), parse_sign, take_bits(7u8)).parse(input)?;
 
     let vrate = vrate_value
         .checked_sub(1)
-        .and_then(|v| v.checked_mul(64))
-        .map(|v| (v as i16) * vrate_sign)
-        .ok_or_else(|| Err::Error(make_error(input, ErrorKind::TooLarge)))?;
+        .map(|v| (v * 64) as i16 * vrate_sign);
 
     let message = ADSBMessageKind::AirborneVelocity {
-        heading,
-        ground_speed: (v_ew.powi(2) + v_ns.powi(2)).sqrt(),
+        velocity,
+        supersonic,
+        intent_change: intent_change == 1,
+        ifr_capability: ifr_capability == 1,
+        navigation_accuracy,
         vertical_rate: vrate,
+        vertical_rate_saturated: vrate_value == MAX_VERTICAL_RATE,
         vertical_rate_source: vrate_src,
+        gnss_baro_difference: (diff_value != 0).then(|| (diff_value - 1) * 25 * diff_sign),
+    };
+    Ok((input, message))
+}
+
+fn decode_emergency_state(state: u8) -> EmergencyState {
+    use EmergencyState::*;
+    match state {
+        0 => NoEmergency,
+        1 => GeneralEmergency,
+        2 => LifeguardMedical,
+        3 => MinimumFuel,
+        4 => NoCommunications,
+        5 => UnlawfulInterference,
+        6 => DownedAircraft,
+        _ => Reserved,
+    }
+}
+
+fn parse_aircraft_status(input: (&[u8], usize)) -> IResult<(&[u8], usize), ADSBMessageKind> {
+    let (input, (_, _, emergency_state, id_code, _)): (_, (u8, u8, u8, u16, u32)) = (
+        verify(take_bits(5u8), |tc| *tc == 28),
+        verify(take_bits(3u8), |st| *st == 1),
+        take_bits(3u8),
+        take_bits(13u8),
+        take_bits(32u8),
+    )
+        .parse(input)?;
+
+    let message = ADSBMessageKind::AircraftStatus {
+        emergency_state: decode_emergency_state(emergency_state),
+        squawk: Squawk::from(decode_id_13_field(id_code)),
+    };
+    Ok((input, message))
+}
+
+// The ME field of RA broadcasts has the same layout as the MB field of BDS 3,0, with the type code
+// and subtype in place of the BDS code
+fn parse_acas_resolution_advisory(
+    input: (&[u8], usize),
+) -> IResult<(&[u8], usize), ADSBMessageKind> {
+    let (input, me) =
+        verify(parse_message_field, |me: &[u8; 7]| me[0] == (28 << 3) | 2).parse(input)?;
+    let message = ADSBMessageKind::ACASResolutionAdvisory {
+        resolution_advisory: decode_resolution_advisory(&me),
     };
     Ok((input, message))
 }
@@ -169,8 +395,11 @@ fn parse_icao_address(input: (&[u8], usize)) -> IResult<(&[u8], usize), ICAOAddr
 fn parse_adsb_message_kind(input: (&[u8], usize)) -> IResult<(&[u8], usize), ADSBMessageKind> {
     alt((
         parse_aircraft_identification,
+        parse_surface_position,
         parse_airborne_position,
         parse_airborne_velocity,
+        parse_aircraft_status,
+        parse_acas_resolution_advisory,
     ))
     .parse(input)
 }
@@ -276,8 +505,238 @@ fn decode_id_13_field(f: u16) -> u16 {
     hex_gillham
 }
 
+// Converts a Gillham coded altitude, with its bits rearranged by `decode_id_13_field`, into an
+// altitude in 100 foot increments.
+//
+// This code is translated from https://github.com/wiedehopf/readsb/blob/dev/mode_s.c
+
+fn mode_a_to_mode_c(mode_a: u16) -> Option<i32> {
+    let mut five_hundreds: i32 = 0;
+    let mut one_hundreds: i32 = 0;
+
+    // Zero bits must be zero, D1 set is illegal and C1..C4 cannot all be zero
+    if mode_a & 0x8889 != 0 || mode_a & 0x00F0 == 0 {
+        return None;
+    }
+
+    if mode_a & 0x0010 != 0 {
+        one_hundreds ^= 0x007;
+    } // C1
+    if mode_a & 0x0020 != 0 {
+        one_hundreds ^= 0x003;
+    } // C2
+    if mode_a & 0x0040 != 0 {
+        one_hundreds ^= 0x001;
+    } // C4
+
+    // Remove 7s from one hundreds (make 7->5 and 5->7)
+    if one_hundreds & 5 == 5 {
+        one_hundreds ^= 2;
+    }
+
+    // Only 1 to 5 are valid
+    if one_hundreds > 5 {
+        return None;
+    }
+
+    if mode_a & 0x0002 != 0 {
+        five_hundreds ^= 0x0FF;
+    } // D2
+    if mode_a & 0x0004 != 0 {
+        five_hundreds ^= 0x07F;
+    } // D4
+    if mode_a & 0x1000 != 0 {
+        five_hundreds ^= 0x03F;
+    } // A1
+    if mode_a & 0x2000 != 0 {
+        five_hundreds ^= 0x01F;
+    } // A2
+    if mode_a & 0x4000 != 0 {
+        five_hundreds ^= 0x00F;
+    } // A4
+    if mode_a & 0x0100 != 0 {
+        five_hundreds ^= 0x007;
+    } // B1
+    if mode_a & 0x0200 != 0 {
+        five_hundreds ^= 0x003;
+    } // B2
+    if mode_a & 0x0400 != 0 {
+        five_hundreds ^= 0x001;
+    } // B4
+
+    // Correct order of one hundreds
+    if five_hundreds & 1 != 0 {
+        one_hundreds = 6 - one_hundreds;
+    }
+
+    Some(five_hundreds * 5 + one_hundreds - 13)
+}
+
+// The 13 bit altitude code (AC) field is laid out as follows (message bit 20 to bit 32):
+//
+// C1-A1-C2-A2-C4-A4-M-B1-Q-B2-D2-B4-D4
+//
+// If the M bit is set the remaining 12 bits contain the altitude in metres. Otherwise, if the Q bit
+// is set, the remaining 11 bits contain the altitude in 25 foot increments and if it is not set
+// they contain a Gillham coded altitude in 100 foot increments. A field with all bits set to zero
+// indicates the altitude is not available, which is distinct from an invalid Gillham code.
+
+pub(crate) fn decode_ac13_field(f: u16) -> AltitudeField {
+    if f == 0 {
+        return Ok(None);
+    }
+    if f & 0x0040 != 0 {
+        let value = ((f & 0x1F80) >> 1) | (f & 0x003F);
+        return Ok(Some(Altitude {
+            value: value.into(),
+            source: AltitudeSource::Metric,
+        }));
+    }
+    if f & 0x0010 != 0 {
+        let n = ((f & 0x1F80) >> 2) | ((f & 0x0020) >> 1) | (f & 0x000F);
+        return Ok(Some(Altitude {
+            value: i32::from(n) * 25 - 1000,
+            source: AltitudeSource::Barometric25Ft,
+        }));
+    }
+    mode_a_to_mode_c(decode_id_13_field(f))
+        .filter(|n| *n >= -12)
+        .map(|n| {
+            Some(Altitude {
+                value: n * 100,
+                source: AltitudeSource::Barometric100Ft,
+            })
+        })
+        .ok_or(AltitudeError::InvalidGillhamCode)
+}
+
+fn parse_vertical_status(input: (&[u8], usize)) -> IResult<(&[u8], usize), VerticalStatus> {
+    alt((
+        map(tag_bits(0b0, 1u8), |_| VerticalStatus::Airborne),
+        map(tag_bits(0b1, 1u8), |_| VerticalStatus::OnGround),
+    ))
+    .parse(input)
+}
+
+fn decode_reply_information(ri: u8) -> ReplyInformation {
+    use ReplyInformation::*;
+    match ri {
+        0 => NoOperatingACAS,
+        2 => ACASResolutionInhibited,
+        3 => ACASVerticalOnly,
+        4 => ACASVerticalAndHorizontal,
+        8 => MaximumAirspeedUnavailable,
+        9 => MaximumAirspeedAtMost(75),
+        10 => MaximumAirspeedAtMost(150),
+        11 => MaximumAirspeedAtMost(300),
+        12 => MaximumAirspeedAtMost(600),
+        13 => MaximumAirspeedAtMost(1200),
+        14 => MaximumAirspeedAbove(1200),
+        _ => Reserved(ri),
+    }
+}
+
+fn parse_reply_information(input: (&[u8], usize)) -> IResult<(&[u8], usize), ReplyInformation> {
+    map(take_bits(4u8), decode_reply_information).parse(input)
+}
+
+// Parses a 56 bit message field, such as the Comm-B (MB) or Comm-V (MV) fields of long replies.
+fn parse_message_field(input: (&[u8], usize)) -> IResult<(&[u8], usize), [u8; 7]> {
+    let mut field = [0u8; 7];
+    let (input, ()) = fill(take_bits(8u8), &mut field).parse(input)?;
+    Ok((input, field))
+}
+
+fn parse_short_air_air_surveillance(
+    input: (&[u8], usize),
+) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let (input, (_df, vertical_status, cross_link, _, sensitivity_level, _, reply_information)): (
+        _,
+        (u8, VerticalStatus, u8, u8, u8, u8, ReplyInformation),
+    ) = (
+        tag_bits(0b00000, 5u8),
+        parse_vertical_status,
+        take_bits(1u8),
+        take_bits(1u8),
+        take_bits(3u8),
+        take_bits(2u8),
+        parse_reply_information,
+    )
+        .parse(input)?;
+    let (input, (_, altitude_code, _parity)): (_, (u8, u16, u32)) =
+        (take_bits(2u8), take_bits(13u8), take_bits(24u8)).parse(input)?;
+    Ok((
+        input,
+        ModeSMessageKind::ShortAirAirSurveillance {
+            vertical_status,
+            cross_link_capability: cross_link == 1,
+            sensitivity_level,
+            reply_information,
+            altitude: decode_ac13_field(altitude_code),
+        },
+    ))
+}
+
+fn parse_long_air_air_surveillance(
+    input: (&[u8], usize),
+) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let (input, (_df, vertical_status, _, sensitivity_level, _, reply_information)): (
+        _,
+        (u8, VerticalStatus, u8, u8, u8, ReplyInformation),
+    ) = (
+        tag_bits(0b10000, 5u8),
+        parse_vertical_status,
+        take_bits(2u8),
+        take_bits(3u8),
+        take_bits(2u8),
+        parse_reply_information,
+    )
+        .parse(input)?;
+    let (input, (_, altitude_code, mv, _parity)): (_, (u8, u16, [u8; 7], u32)) = (
+        take_bits(2u8),
+        take_bits(13u8),
+        parse_message_field,
+        take_bits(24u8),
+    )
+        .parse(input)?;
+    Ok((
+        input,
+        ModeSMessageKind::LongAirAirSurveillance {
+            vertical_status,
+            sensitivity_level,
+            reply_information,
+            altitude: decode_ac13_field(altitude_code),
+            mv,
+        },
+    ))
+}
+
+fn parse_surveillance_altitude(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let (input, (_df, flight_status, downlink_request, utility_message, altitude_code, _parity)): (
+        _,
+        (u8, u8, u8, u8, u16, u32),
+    ) = (
+        tag_bits(0b00100, 5u8),
+        take_bits(3u8),
+        take_bits(5u8),
+        take_bits(6u8),
+        take_bits(13u8),
+        take_bits(24u8),
+    )
+        .parse(input)?;
+    Ok((
+        input,
+        ModeSMessageKind::SurveillanceAltitude {
+            flight_status,
+            downlink_request,
+            utility_message,
+            altitude: decode_ac13_field(altitude_code),
+        },
+    ))
+}
+
 fn parse_surveillance_identity(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
-    let (_input, (_df, _flight_status, _downlink_req, _utility_msg, id_code, _parity)): (
+    let (input, (_df, _flight_status, _downlink_req, _utility_msg, id_code, _parity)): (
         _,
         (u8, u8, u8, u8, u16, u32),
     ) = (
@@ -298,23 +757,131 @@ fn parse_surveillance_identity(input: (&[u8], usize)) -> IResult<(&[u8], usize),
     ))
 }
 
+// In all-call replies the parity field is overlaid with a 7 bit code made of a 3 bit code label
+// (CL) followed by a 4 bit interrogator code (IC). A code label of 0 means the interrogator code is
+// an II code, while code labels 1 to 4 mean it contains SI codes 1-15, 16-31, 32-47 and 48-63
+// respectively. Any other remainder indicates the message was corrupted.
+
+fn decode_interrogator_identifier(rem: u32) -> Option<InterrogatorIdentifier> {
+    let code_label = rem >> 4;
+    let code = (rem & 0x0F) as u8;
+    match code_label {
+        0 => Some(InterrogatorIdentifier::InterrogatorCode(code)),
+        1..=4 => Some(InterrogatorIdentifier::SurveillanceCode(
+            (code_label as u8 - 1) * 16 + code,
+        )),
+        _ => None,
+    }
+}
+
+fn parse_all_call_reply(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let start = input.0;
+    let (input, (_df, capability, icao_address, _parity)): (_, (u8, u8, ICAOAddress, u32)) = (
+        tag_bits(0b01011, 5u8),
+        take_bits(3u8),
+        parse_icao_address,
+        take_bits(24u8),
+    )
+        .parse(input)?;
+    let payload = &start[0..(start.len() - input.0.len())];
+    let rem = get_crc_remainder(payload)
+        .map_err(|_| Err::Error(make_error(input, ErrorKind::LengthValue)))?;
+    Ok((
+        input,
+        ModeSMessageKind::AllCallReply {
+            capability,
+            icao_address,
+            interrogator: decode_interrogator_identifier(rem),
+        },
+    ))
+}
+
+fn parse_comm_b_altitude_reply(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let (input, (_df, flight_status, downlink_request, utility_message, altitude_code)): (
+        _,
+        (u8, u8, u8, u8, u16),
+    ) = (
+        tag_bits(0b10100, 5u8),
+        take_bits(3u8),
+        take_bits(5u8),
+        take_bits(6u8),
+        take_bits(13u8),
+    )
+        .parse(input)?;
+    let (input, (mb, _parity)): (_, ([u8; 7], u32)) =
+        (parse_message_field, take_bits(24u8)).parse(input)?;
+    Ok((
+        input,
+        ModeSMessageKind::CommBAltitudeReply {
+            flight_status,
+            downlink_request,
+            utility_message,
+            altitude: decode_ac13_field(altitude_code),
+            mb,
+        },
+    ))
+}
+
+fn parse_comm_b_identity_reply(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
+    let (input, (_df, flight_status, downlink_request, utility_message, id_code)): (
+        _,
+        (u8, u8, u8, u8, u16),
+    ) = (
+        tag_bits(0b10101, 5u8),
+        take_bits(3u8),
+        take_bits(5u8),
+        take_bits(6u8),
+        take_bits(13u8),
+    )
+        .parse(input)?;
+    let (input, (mb, _parity)): (_, ([u8; 7], u32)) =
+        (parse_message_field, take_bits(24u8)).parse(input)?;
+    Ok((
+        input,
+        ModeSMessageKind::CommBIdentityReply {
+            flight_status,
+            downlink_request,
+            utility_message,
+            squawk: Squawk::from(decode_id_13_field(id_code)),
+            mb,
+        },
+    ))
+}
+
 fn parse_mode_s_message_kind(input: (&[u8], usize)) -> IResult<(&[u8], usize), ModeSMessageKind> {
-    parse_surveillance_identity(input)
+    alt((
+        parse_surveillance_altitude,
+        parse_surveillance_identity,
+        parse_all_call_reply,
+        parse_short_air_air_surveillance,
+        parse_long_air_air_surveillance,
+        parse_comm_b_altitude_reply,
+        parse_comm_b_identity_reply,
+    ))
+    .parse(input)
 }
 
+// Mode S replies other than DF 11/17/18 overlay the aircraft address on the parity field, so the
+// CRC remainder of the whole message is the aircraft address. All-call replies (DF 11) announce the
+// address explicitly instead.
 fn parse_mode_s_message(input: (&[u8], usize)) -> IResult<(&[u8], usize), MessageKind> {
+    let start = input.0;
     let (input, kind) = parse_mode_s_message_kind(input)?;
-    let rem = get_crc_remainder(&input.0[0..7])
-        .map_err(|_| Err::Error(make_error(input, ErrorKind::LengthValue)))?;
-    let icao = (
-        (rem & 0xFF0000) >> 16,
-        (rem & 0x00FF00) >> 8,
-        rem & 0x0000FF,
-    );
-    let message = MessageKind::ModeSMessage {
-        icao_address: ICAOAddress(icao.0 as u8, icao.1 as u8, icao.2 as u8),
-        kind,
+    let icao_address = match kind {
+        ModeSMessageKind::AllCallReply { icao_address, .. } => icao_address,
+        _ => {
+            let payload = &start[0..(start.len() - input.0.len())];
+            let rem = get_crc_remainder(payload)
+                .map_err(|_| Err::Error(make_error(input, ErrorKind::LengthValue)))?;
+            let icao = (
+                (rem & 0xFF0000) >> 16,
+                (rem & 0x00FF00) >> 8,
+                rem & 0x0000FF,
+            );
+            ICAOAddress(icao.0 as u8, icao.1 as u8, icao.2 as u8)
+        }
     };
+    let message = MessageKind::ModeSMessage { icao_address, kind };
 
     Ok((input, message))
 }
@@ -407,6 +974,213 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_mode_s_surveillance_altitude_25ft() {
+        let r = b"\x20\x00\x18\x38\xCA\x38\x04";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 4);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0xDB, 0xBB, 0x5F),
+                kind: ModeSMessageKind::SurveillanceAltitude {
+                    flight_status: 0,
+                    downlink_request: 0,
+                    utility_message: 0,
+                    altitude: Ok(Some(Altitude {
+                        value: 38000,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_surveillance_altitude_gillham() {
+        let r = b"\x21\x00\x04\x88\xDC\x9B\x76";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x48, 0x40, 0xD6),
+                kind: ModeSMessageKind::SurveillanceAltitude {
+                    flight_status: 1,
+                    downlink_request: 0,
+                    utility_message: 0,
+                    altitude: Ok(Some(Altitude {
+                        value: 5000,
+                        source: AltitudeSource::Barometric100Ft,
+                    })),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn decode_altitude_code() {
+        assert_eq!(decode_ac13_field(0), Ok(None));
+        assert_eq!(
+            decode_ac13_field(0x0400),
+            Ok(Some(Altitude {
+                value: -1000,
+                source: AltitudeSource::Barometric100Ft,
+            }))
+        );
+        assert_eq!(
+            decode_ac13_field(0x0FFF),
+            Ok(Some(Altitude {
+                value: 2047,
+                source: AltitudeSource::Metric,
+            }))
+        );
+        // C1, C2 and C4 all zero is not a valid Gillham code
+        assert_eq!(
+            decode_ac13_field(0x0020),
+            Err(AltitudeError::InvalidGillhamCode)
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_all_call_reply_surveillance_code() {
+        let r = b"\x5D\x48\x4F\xDE\xA2\x48\xF5";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 11);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x48, 0x4F, 0xDE),
+                kind: ModeSMessageKind::AllCallReply {
+                    capability: CAPABILITY,
+                    icao_address: ICAOAddress(0x48, 0x4F, 0xDE),
+                    interrogator: Some(InterrogatorIdentifier::SurveillanceCode(6)),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_all_call_reply_interrogator_code() {
+        let r = b"\x5D\x4D\x20\x23\x7A\x55\xA6";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x4D, 0x20, 0x23),
+                kind: ModeSMessageKind::AllCallReply {
+                    capability: CAPABILITY,
+                    icao_address: ICAOAddress(0x4D, 0x20, 0x23),
+                    interrogator: Some(InterrogatorIdentifier::InterrogatorCode(0)),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_all_call_reply_invalid_parity() {
+        let r = b"\x5D\x4D\x20\x23\x7B\x55\xA6";
+        let (_, m) = parse_message(r).unwrap();
+        assert!(matches!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                kind: ModeSMessageKind::AllCallReply {
+                    interrogator: None,
+                    ..
+                },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parse_mode_s_short_air_air_surveillance() {
+        let r = b"\x02\xE1\x97\xB0\x01\x79\xC3";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 0);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x4B, 0x18, 0xFE),
+                kind: ModeSMessageKind::ShortAirAirSurveillance {
+                    vertical_status: VerticalStatus::Airborne,
+                    cross_link_capability: true,
+                    sensitivity_level: 7,
+                    reply_information: ReplyInformation::ACASVerticalOnly,
+                    altitude: Ok(Some(Altitude {
+                        value: 37000,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_long_air_air_surveillance() {
+        let r = b"\x80\xE2\x17\xB0\x30\xA0\x00\x00\x00\x00\x00\xCB\x7D\x48";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 16);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x3C, 0x65, 0x86),
+                kind: ModeSMessageKind::LongAirAirSurveillance {
+                    vertical_status: VerticalStatus::Airborne,
+                    sensitivity_level: 7,
+                    reply_information: ReplyInformation::ACASVerticalAndHorizontal,
+                    altitude: Ok(Some(Altitude {
+                        value: 37000,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                    mv: [0x30, 0xA0, 0x00, 0x00, 0x00, 0x00, 0x00],
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_comm_b_altitude_reply() {
+        let r = b"\xA0\x00\x13\x93\x81\x95\x15\x36\xE0\x24\xD4\xCC\xF6\xB5";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 20);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x3C, 0x4D, 0xD2),
+                kind: ModeSMessageKind::CommBAltitudeReply {
+                    flight_status: 0,
+                    downlink_request: 0,
+                    utility_message: 0,
+                    altitude: Ok(Some(Altitude {
+                        value: 30275,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                    mb: [0x81, 0x95, 0x15, 0x36, 0xE0, 0x24, 0xD4],
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_mode_s_comm_b_identity_reply() {
+        let r = b"\xA8\x00\x08\x08\x20\x2C\xC3\x71\xC3\x1D\xE0\xA6\x35\xCB";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 21);
+        assert_eq!(
+            m.kind,
+            MessageKind::ModeSMessage {
+                icao_address: ICAOAddress(0x48, 0x41, 0x75),
+                kind: ModeSMessageKind::CommBIdentityReply {
+                    flight_status: 0,
+                    downlink_request: 0,
+                    utility_message: 0,
+                    squawk: Squawk::from_str("1200").unwrap(),
+                    mb: [0x20, 0x2C, 0xC3, 0x71, 0xC3, 0x1D, 0xE0],
+                }
+            }
+        );
+    }
+
     #[test]
     fn parse_adsb_aircraft_identification_message() {
         let r = b"\x8D\x48\x40\xD6\x20\x2C\xC3\x71\xC3\x2C\xE0\x57\x60\x98";
@@ -439,12 +1213,20 @@ mod tests {
                 icao_address: ICAOAddress(0x40, 0x62, 0x1D),
                 type_code: 11,
                 kind: ADSBMessageKind::AirbornePosition {
-                    altitude: 38000,
+                    altitude: Ok(Some(Altitude {
+                        value: 38000,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                    surveillance_status: SurveillanceStatus::NoCondition,
+                    nic_supplement_b: false,
+                    utc_synchronized: false,
+                    containment_radius: Some(185.2),
                     cpr_frame: CPRFrame {
                         parity: Parity::Even,
-                        position: Position {
-                            latitude: 93000.0,
-                            longitude: 51372.0,
+                        encoding: CPREncoding::Airborne,
+                        position: CPRPosition {
+                            latitude: 93000,
+                            longitude: 51372,
                         }
                     },
                 },
@@ -465,12 +1247,20 @@ mod tests {
                 icao_address: ICAOAddress(0x40, 0x62, 0x1D),
                 type_code: 11,
                 kind: ADSBMessageKind::AirbornePosition {
-                    altitude: 38000,
+                    altitude: Ok(Some(Altitude {
+                        value: 38000,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                    surveillance_status: SurveillanceStatus::NoCondition,
+                    nic_supplement_b: false,
+                    utc_synchronized: false,
+                    containment_radius: Some(185.2),
                     cpr_frame: CPRFrame {
                         parity: Parity::Odd,
-                        position: Position {
-                            latitude: 74158.0,
-                            longitude: 50194.0,
+                        encoding: CPREncoding::Airborne,
+                        position: CPRPosition {
+                            latitude: 74158,
+                            longitude: 50194,
                         }
                     },
                 },
@@ -479,6 +1269,204 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_adsb_surface_position_message() {
+        let r = b"\x8C\x48\x41\x75\x3A\x9A\x15\x32\x37\xAE\xF0\xF2\x75\xBE";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(m.downlink_format, 17);
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: 4,
+                icao_address: ICAOAddress(0x48, 0x41, 0x75),
+                type_code: 7,
+                kind: ADSBMessageKind::SurfacePosition {
+                    movement: Movement::GroundSpeed(17.0),
+                    ground_track: Some(92.8125),
+                    utc_synchronized: false,
+                    cpr_frame: CPRFrame {
+                        parity: Parity::Odd,
+                        encoding: CPREncoding::Surface,
+                        position: CPRPosition {
+                            latitude: 39195,
+                            longitude: 110320,
+                        }
+                    },
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn decode_surface_movement() {
+        assert_eq!(decode_movement(0), Movement::Unavailable);
+        assert_eq!(decode_movement(1), Movement::Stopped);
+        assert_eq!(decode_movement(8), Movement::GroundSpeed(0.875));
+        assert_eq!(decode_movement(12), Movement::GroundSpeed(1.75));
+        assert_eq!(decode_movement(94), Movement::GroundSpeed(70.0));
+        assert_eq!(decode_movement(123), Movement::GroundSpeed(170.0));
+        assert_eq!(decode_movement(124), Movement::AtLeast175Knots);
+        assert_eq!(decode_movement(127), Movement::Reserved(127));
+    }
+
+    #[test]
+    fn parse_adsb_airborne_velocity_supersonic_ground_speed() {
+        let r = b"\x8D\x40\x62\x1D\x9A\x01\x2D\x00\x20\x04\x00\x89\x87\x7E";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0x40, 0x62, 0x1D),
+                type_code: 19,
+                kind: ADSBMessageKind::AirborneVelocity {
+                    velocity: Velocity::GroundSpeed {
+                        east_west: Some(1200),
+                        north_south: Some(0),
+                        heading: Some(90.0),
+                        ground_speed: Some(1200.0),
+                        saturated: false,
+                    },
+                    supersonic: true,
+                    intent_change: false,
+                    ifr_capability: false,
+                    navigation_accuracy: 0,
+                    vertical_rate: Some(0),
+                    vertical_rate_saturated: false,
+                    vertical_rate_source: VerticalRateSource::BarometricPressureAltitude,
+                    gnss_baro_difference: None,
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adsb_airborne_velocity_unavailable_and_saturated() {
+        let r = b"\x8D\x40\x62\x1D\x99\x00\x00\x7F\xE0\x00\x00\x7A\x45\x2E";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0x40, 0x62, 0x1D),
+                type_code: 19,
+                kind: ADSBMessageKind::AirborneVelocity {
+                    velocity: Velocity::GroundSpeed {
+                        east_west: None,
+                        north_south: Some(1022),
+                        heading: None,
+                        ground_speed: None,
+                        saturated: true,
+                    },
+                    supersonic: false,
+                    intent_change: false,
+                    ifr_capability: false,
+                    navigation_accuracy: 0,
+                    vertical_rate: None,
+                    vertical_rate_saturated: false,
+                    vertical_rate_source: VerticalRateSource::BarometricPressureAltitude,
+                    gnss_baro_difference: None,
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adsb_aircraft_status() {
+        let r = b"\x8D\x40\x62\x1D\xE1\x2A\xAA\x00\x00\x00\x00\x45\x93\x0A";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0x40, 0x62, 0x1D),
+                type_code: 28,
+                kind: ADSBMessageKind::AircraftStatus {
+                    emergency_state: EmergencyState::GeneralEmergency,
+                    squawk: Squawk::from_str("7700").unwrap(),
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adsb_acas_resolution_advisory() {
+        use crate::bds::{
+            ActiveResolutionAdvisories, ResolutionAdvisory, ResolutionAdvisoryComplements,
+            ThreatIdentity,
+        };
+
+        let r = b"\x8D\x40\x62\x1D\xE2\xA0\x00\x05\x21\x49\x58\xC4\xA4\xE8";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0x40, 0x62, 0x1D),
+                type_code: 28,
+                kind: ADSBMessageKind::ACASResolutionAdvisory {
+                    resolution_advisory: ResolutionAdvisory {
+                        active_resolution_advisories: ActiveResolutionAdvisories::SingleSense {
+                            corrective: false,
+                            downward_sense: true,
+                            increased_rate: false,
+                            sense_reversal: false,
+                            altitude_crossing: false,
+                            positive: false,
+                        },
+                        complements: ResolutionAdvisoryComplements {
+                            do_not_pass_below: false,
+                            do_not_pass_above: false,
+                            do_not_turn_left: false,
+                            do_not_turn_right: false,
+                        },
+                        terminated: false,
+                        multiple_threat_encounter: false,
+                        threat_identity: Some(ThreatIdentity::Address(ICAOAddress(
+                            0x48, 0x52, 0x56
+                        ))),
+                    },
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adsb_airborne_velocity_airspeed() {
+        let r = b"\x8D\xA0\x5F\x21\x9B\x06\xB6\xAF\x18\x94\x00\xCB\xC3\x3F";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0xA0, 0x5F, 0x21),
+                type_code: 19,
+                kind: ADSBMessageKind::AirborneVelocity {
+                    velocity: Velocity::Airspeed {
+                        heading: Some(243.984375),
+                        airspeed: Some(375),
+                        airspeed_type: AirspeedType::True,
+                        saturated: false,
+                    },
+                    supersonic: false,
+                    intent_change: false,
+                    ifr_capability: false,
+                    navigation_accuracy: 0,
+                    vertical_rate: Some(-2304),
+                    vertical_rate_saturated: false,
+                    vertical_rate_source: VerticalRateSource::GeometricAltitude,
+                    gnss_baro_difference: None,
+                },
+                crc: true,
+            }
+        );
+    }
+
     #[test]
     fn parse_adsb_airborne_velocity_ground_speed() {
         let r = b"\x8D\x48\x50\x20\x99\x44\x09\x94\x08\x38\x17\x5B\x28\x4F";
@@ -491,16 +1479,171 @@ mod tests {
                 icao_address: ICAOAddress(0x48, 0x50, 0x20),
                 type_code: 19,
                 kind: ADSBMessageKind::AirborneVelocity {
-                    heading: 182.8803775528476,
-                    ground_speed: 159.20113064925135,
-                    vertical_rate: -832,
+                    velocity: Velocity::GroundSpeed {
+                        east_west: Some(-8),
+                        north_south: Some(-159),
+                        heading: Some(182.8803775528476),
+                        ground_speed: Some(159.20113064925135),
+                        saturated: false,
+                    },
+                    supersonic: false,
+                    intent_change: false,
+                    ifr_capability: true,
+                    navigation_accuracy: 0,
+                    vertical_rate: Some(-832),
+                    vertical_rate_saturated: false,
                     vertical_rate_source: VerticalRateSource::BarometricPressureAltitude,
+                    gnss_baro_difference: Some(550),
                 },
                 crc: true,
             }
         );
     }
 
+    #[test]
+    fn parse_adsb_airborne_position_surveillance_status() {
+        let r = b"\x8D\x40\x62\x1D\x5F\xB5\x0A\xD6\x90\xC8\xAC\xDF\xB8\x7D";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind:
+                    ADSBMessageKind::AirbornePosition {
+                        altitude,
+                        surveillance_status,
+                        nic_supplement_b,
+                        utc_synchronized,
+                        ..
+                    },
+                ..
+            } => {
+                assert_eq!(
+                    altitude,
+                    Ok(Some(Altitude {
+                        value: 35000,
+                        source: AltitudeSource::Barometric25Ft
+                    }))
+                );
+                assert_eq!(surveillance_status, SurveillanceStatus::SPICondition);
+                assert!(nic_supplement_b);
+                assert!(utc_synchronized);
+            }
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_gillham_altitude() {
+        let r = b"\x8D\x40\x62\x1D\x58\xA6\x22\xD6\x90\xC8\xAC\xFE\x90\x44";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind: ADSBMessageKind::AirbornePosition { altitude, .. },
+                ..
+            } => assert_eq!(
+                altitude,
+                Ok(Some(Altitude {
+                    value: 3400,
+                    source: AltitudeSource::Barometric100Ft
+                }))
+            ),
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_negative_altitude() {
+        let r = b"\x8D\x40\x62\x1D\x58\x03\x42\xD6\x90\xC8\xAC\xB6\x9D\xEA";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind: ADSBMessageKind::AirbornePosition { altitude, .. },
+                ..
+            } => assert_eq!(
+                altitude,
+                Ok(Some(Altitude {
+                    value: -500,
+                    source: AltitudeSource::Barometric25Ft
+                }))
+            ),
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_altitude_unavailable() {
+        let r = b"\x8D\x40\x62\x1D\x58\x00\x02\xD6\x90\xC8\xAC\x94\xB0\x55";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind: ADSBMessageKind::AirbornePosition { altitude, .. },
+                ..
+            } => assert_eq!(altitude, Ok(None)),
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_invalid_gillham_altitude() {
+        // C1, C2 and C4 all zero is not a valid Gillham code, unlike an all-zero field
+        let r = b"\x8D\x40\x62\x1D\x58\x02\x02\xD6\x90\xC8\xAC\x81\x08\x78";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind: ADSBMessageKind::AirbornePosition { altitude, .. },
+                ..
+            } => assert_eq!(altitude, Err(AltitudeError::InvalidGillhamCode)),
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_gnss_height_message() {
+        // Synthetic frame reporting a height of -300 ft below the ellipsoid
+        let r = b"\x8D\x40\x62\x1D\xA0\x03\xC2\xD6\x90\xC8\xAC\xB8\xE9\x8E";
+        let (_, m) = parse_message(r).unwrap();
+        assert_eq!(
+            m.kind,
+            MessageKind::ADSBMessage {
+                capability: CAPABILITY,
+                icao_address: ICAOAddress(0x40, 0x62, 0x1D),
+                type_code: 20,
+                kind: ADSBMessageKind::AirbornePosition {
+                    altitude: Ok(Some(Altitude {
+                        value: -300,
+                        source: AltitudeSource::Gnss,
+                    })),
+                    surveillance_status: SurveillanceStatus::NoCondition,
+                    nic_supplement_b: false,
+                    utc_synchronized: false,
+                    containment_radius: Some(7.5),
+                    cpr_frame: CPRFrame {
+                        parity: Parity::Even,
+                        encoding: CPREncoding::Airborne,
+                        position: CPRPosition {
+                            latitude: 93000,
+                            longitude: 51372,
+                        }
+                    },
+                },
+                crc: true,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adsb_airborne_position_gillham_coded_gnss_height() {
+        // Synthetic frame with the Q bit of the GNSS height cleared
+        let r = b"\x8D\x40\x62\x1D\xA0\x02\x82\xD6\x90\xC8\xAC\x8F\x7C\x1C";
+        let (_, m) = parse_message(r).unwrap();
+        match m.kind {
+            MessageKind::ADSBMessage {
+                kind: ADSBMessageKind::AirbornePosition { altitude, .. },
+                ..
+            } => assert_eq!(altitude, Err(AltitudeError::GillhamCodedGnssHeight)),
+            _ => panic!("unexpected message: {:?}", m),
+        }
+    }
+
     #[test]
     fn parse_adsb_df18_airborne_position_even_message() {
         // This is a TIS-B message.
@@ -514,12 +1657,20 @@ mod tests {
                 icao_address: ICAOAddress(0x29, 0x82, 0xE5),
                 type_code: 13,
                 kind: ADSBMessageKind::AirbornePosition {
-                    altitude: 4400,
+                    altitude: Ok(Some(Altitude {
+                        value: 4400,
+                        source: AltitudeSource::Barometric25Ft,
+                    })),
+                    surveillance_status: SurveillanceStatus::NoCondition,
+                    nic_supplement_b: false,
+                    utc_synchronized: false,
+                    containment_radius: Some(1111.2),
                     cpr_frame: CPRFrame {
                         parity: Parity::Even,
-                        position: Position {
-                            latitude: 88341.0,
-                            longitude: 112614.0,
+                        encoding: CPREncoding::Airborne,
+                        position: CPRPosition {
+                            latitude: 88341,
+                            longitude: 112614,
                         }
                     },
                 },
@@ -1,3 +1,4 @@
+use crate::bds::ResolutionAdvisory;
 use std::convert::From;
 use std::error::Error;
 use std::fmt;
@@ -67,12 +68,46 @@ pub struct Position {
 
 /// Aircraft position is broadcast as a set of alternating odd and even frames
 /// which encode position information using Compact Position Reporting (CPR).
+/// Frames are decoded into a [`Position`] using the [`crate::cpr`] module.
 #[derive(Debug, PartialEq, Clone)]
 pub struct CPRFrame {
     /// Aircraft position in CPR format
-    pub position: Position,
+    pub position: CPRPosition,
     /// Frame parity
     pub parity: Parity,
+    /// Encoding of the position
+    pub encoding: CPREncoding,
+}
+
+/// Raw coordinates of a position encoded in CPR format, as transmitted.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CPRPosition {
+    /// Encoded latitude
+    pub latitude: u32,
+    /// Encoded longitude
+    pub longitude: u32,
+}
+
+/// Encoding of a position in CPR format, which determines the resolution of the coordinates and
+/// the size of the zones used to decode them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CPREncoding {
+    /// 17-bit coordinates of an airborne position, decoded using 360 degree zones
+    Airborne,
+    /// 17-bit coordinates of a surface position, decoded using 90 degree zones
+    Surface,
+    /// 12-bit coordinates of a coarse TIS-B position, decoded using 360 degree zones
+    Coarse,
+}
+
+impl CPREncoding {
+    /// Number of bits used to encode each coordinate.
+    pub fn bits(&self) -> u32 {
+        match self {
+            CPREncoding::Airborne | CPREncoding::Surface => 17,
+            CPREncoding::Coarse => 12,
+        }
+    }
 }
 
 /// Frame parity.
@@ -82,6 +117,96 @@ pub enum Parity {
     Odd,
 }
 
+/// Ground movement reported in surface position messages.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Movement {
+    /// No movement information available
+    Unavailable,
+    /// Aircraft is stopped, with a ground speed below 0.125 knots
+    Stopped,
+    /// Ground speed in knots, rounded down to the resolution of its encoding
+    GroundSpeed(f64),
+    /// Ground speed is 175 knots or more
+    AtLeast175Knots,
+    /// Reserved value
+    Reserved(u8),
+}
+
+/// Surveillance status reported in airborne position messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SurveillanceStatus {
+    /// No condition reported
+    NoCondition,
+    /// Permanent alert, indicating an emergency
+    PermanentAlert,
+    /// Temporary alert, indicating a change of the Mode A identity code
+    TemporaryAlert,
+    /// Special Position Identification (SPI) condition
+    SPICondition,
+}
+
+/// Horizontal velocity reported in airborne velocity messages.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Velocity {
+    /// Velocity over ground (subtypes 1 and 2)
+    GroundSpeed {
+        /// East-west velocity component in knots, positive towards the east, if available
+        east_west: Option<i16>,
+        /// North-south velocity component in knots, positive towards the north, if available
+        north_south: Option<i16>,
+        /// Heading in degrees, if both velocity components are available
+        heading: Option<f64>,
+        /// Ground speed in knots, if both velocity components are available
+        ground_speed: Option<f64>,
+        /// True if a velocity component exceeds the maximum value that can be encoded (1021.5
+        /// knots, or 4086 knots for supersonic aircraft), so the actual speed is higher
+        saturated: bool,
+    },
+    /// Airspeed and heading, reported when velocity over ground is not available (subtypes 3
+    /// and 4)
+    Airspeed {
+        /// Magnetic heading in degrees, if available
+        heading: Option<f64>,
+        /// Airspeed in knots, if available
+        airspeed: Option<u16>,
+        /// Type of airspeed
+        airspeed_type: AirspeedType,
+        /// True if the airspeed exceeds the maximum value that can be encoded (1021.5 knots, or
+        /// 4086 knots for supersonic aircraft), so the actual airspeed is higher
+        saturated: bool,
+    },
+}
+
+/// Type of airspeed reported in airborne velocity messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AirspeedType {
+    /// Indicated airspeed (IAS)
+    Indicated,
+    /// True airspeed (TAS)
+    True,
+}
+
+/// Emergency or priority status reported in aircraft status messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EmergencyState {
+    /// No emergency
+    NoEmergency,
+    /// General emergency (squawk 7700)
+    GeneralEmergency,
+    /// Lifeguard or medical emergency
+    LifeguardMedical,
+    /// Minimum fuel
+    MinimumFuel,
+    /// No communications (squawk 7600)
+    NoCommunications,
+    /// Unlawful interference (squawk 7500)
+    UnlawfulInterference,
+    /// Downed aircraft
+    DownedAircraft,
+    /// Reserved value
+    Reserved,
+}
+
 /// Source for vertical rate information.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum VerticalRateSource {
@@ -91,6 +216,92 @@ pub enum VerticalRateSource {
     GeometricAltitude,
 }
 
+/// Altitude reported by an aircraft.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Altitude {
+    /// Altitude value, in feet or metres depending on the source
+    pub value: i32,
+    /// Encoding the altitude was decoded from
+    pub source: AltitudeSource,
+}
+
+/// Encoding used to report an altitude.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AltitudeSource {
+    /// Barometric altitude in feet with 25 foot increments
+    Barometric25Ft,
+    /// Barometric altitude in feet with 100 foot increments (Gillham code)
+    Barometric100Ft,
+    /// Barometric altitude in metres
+    Metric,
+    /// GNSS height above the WGS-84 ellipsoid in feet, in 25 ft increments
+    Gnss,
+}
+
+/// Error type used to convey why an altitude field could not be decoded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AltitudeError {
+    /// The field contains a Gillham code that does not correspond to a valid altitude
+    InvalidGillhamCode,
+    /// The GNSS height field is Gillham coded, which only has a 100 ft resolution
+    GillhamCodedGnssHeight,
+}
+
+impl fmt::Display for AltitudeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AltitudeError::InvalidGillhamCode => "altitude has an invalid Gillham code",
+                AltitudeError::GillhamCodedGnssHeight => "GNSS height is Gillham coded",
+            }
+        )
+    }
+}
+
+impl Error for AltitudeError {}
+
+/// Vertical status of an aircraft.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VerticalStatus {
+    /// Aircraft is airborne
+    Airborne,
+    /// Aircraft is on the ground
+    OnGround,
+}
+
+/// Reply information reported in air-air surveillance replies, which conveys either the ACAS
+/// capability or the maximum cruising true airspeed of an aircraft.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ReplyInformation {
+    /// No operating ACAS
+    NoOperatingACAS,
+    /// ACAS with resolution capability inhibited
+    ACASResolutionInhibited,
+    /// ACAS with vertical-only resolution capability
+    ACASVerticalOnly,
+    /// ACAS with vertical and horizontal resolution capability
+    ACASVerticalAndHorizontal,
+    /// No maximum airspeed data available
+    MaximumAirspeedUnavailable,
+    /// Maximum airspeed in knots is at most the given value and above the next lowest range
+    MaximumAirspeedAtMost(u16),
+    /// Maximum airspeed in knots is above the given value
+    MaximumAirspeedAbove(u16),
+    /// Reserved or unassigned value
+    Reserved(u8),
+}
+
+/// Identifier of the interrogator which elicited a Mode S reply.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InterrogatorIdentifier {
+    /// Interrogator identifier (II) code
+    InterrogatorCode(u8),
+    /// Surveillance identifier (SI) code
+    SurveillanceCode(u8),
+}
+
 /// ADS-B/Mode-S message.
 #[derive(Debug, PartialEq)]
 pub struct Message {
@@ -135,6 +346,78 @@ pub enum ModeSMessageKind {
         /// Squawk code
         squawk: Squawk,
     },
+    /// Surveillance Altitude Reply message (DF 4)
+    SurveillanceAltitude {
+        /// Flight status
+        flight_status: u8,
+        /// Downlink request
+        downlink_request: u8,
+        /// Utility message
+        utility_message: u8,
+        /// Altitude, `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+    },
+    /// All-Call Reply message (DF 11)
+    AllCallReply {
+        /// Aircraft transponder capability
+        capability: u8,
+        /// Aircraft ICAO address announced in the reply
+        icao_address: ICAOAddress,
+        /// Identifier of the interrogator, if it could be recovered from the parity field
+        interrogator: Option<InterrogatorIdentifier>,
+    },
+    /// Short Air-Air Surveillance message (DF 0)
+    ShortAirAirSurveillance {
+        /// Vertical status
+        vertical_status: VerticalStatus,
+        /// True if the aircraft supports cross-link capability
+        cross_link_capability: bool,
+        /// ACAS sensitivity level, 0 if ACAS is inoperative
+        sensitivity_level: u8,
+        /// ACAS capability or maximum airspeed
+        reply_information: ReplyInformation,
+        /// Altitude, `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+    },
+    /// Long Air-Air Surveillance message (DF 16)
+    LongAirAirSurveillance {
+        /// Vertical status
+        vertical_status: VerticalStatus,
+        /// ACAS sensitivity level, 0 if ACAS is inoperative
+        sensitivity_level: u8,
+        /// ACAS capability or maximum airspeed
+        reply_information: ReplyInformation,
+        /// Altitude, `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+        /// Comm-V (MV) field carrying ACAS coordination data
+        mv: [u8; 7],
+    },
+    /// Comm-B Altitude Reply message (DF 20)
+    CommBAltitudeReply {
+        /// Flight status
+        flight_status: u8,
+        /// Downlink request
+        downlink_request: u8,
+        /// Utility message
+        utility_message: u8,
+        /// Altitude, `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+        /// Comm-B (MB) field containing the contents of a ground-initiated Comm-B register
+        mb: [u8; 7],
+    },
+    /// Comm-B Identity Reply message (DF 21)
+    CommBIdentityReply {
+        /// Flight status
+        flight_status: u8,
+        /// Downlink request
+        downlink_request: u8,
+        /// Utility message
+        utility_message: u8,
+        /// Squawk code
+        squawk: Squawk,
+        /// Comm-B (MB) field containing the contents of a ground-initiated Comm-B register
+        mb: [u8; 7],
+    },
 }
 
 /// Kind of ADSB message.
@@ -147,24 +430,70 @@ pub enum ADSBMessageKind {
         /// Aircraft callsign
         callsign: String,
     },
-    /// Airborne position message (TC 9-18)
+    /// Airborne position message (TC 9-18 and 20-22)
     AirbornePosition {
-        /// Altitude in feet
-        altitude: u16,
+        /// Barometric altitude (TC 9-18) or GNSS height (TC 20-22), `Ok(None)` if not available
+        altitude: Result<Option<Altitude>, AltitudeError>,
+        /// Surveillance status
+        surveillance_status: SurveillanceStatus,
+        /// NIC supplement B, which was used to indicate a single antenna in earlier versions
+        nic_supplement_b: bool,
+        /// True if the time of applicability is synchronized with UTC
+        utc_synchronized: bool,
+        /// Upper bound in metres of the horizontal containment radius (or horizontal protection
+        /// limit) implied by the type code, which can be lowered by the NIC supplements. `None`
+        /// when the bound is unknown.
+        containment_radius: Option<f64>,
+        /// Odd or even frame encoding position information in CPR format
+        cpr_frame: CPRFrame,
+    },
+    /// Surface position message (TC 5-8)
+    SurfacePosition {
+        /// Ground movement
+        movement: Movement,
+        /// Ground track in degrees relative to true north, if available
+        ground_track: Option<f64>,
+        /// True if the time of applicability of the position is synchronised with UTC
+        utc_synchronized: bool,
         /// Odd or even frame encoding position information in CPR format
         cpr_frame: CPRFrame,
     },
     /// Airborne velocity message (TC 19)
     AirborneVelocity {
-        /// Heading in degrees
-        heading: f64,
-        /// Ground speed in knots
-        ground_speed: f64,
+        /// Horizontal velocity, either over ground or through the air
+        velocity: Velocity,
+        /// True if speeds are encoded with the 4 knot resolution used by supersonic aircraft
+        /// (subtypes 2 and 4)
+        supersonic: bool,
+        /// True if the aircraft has changed its intent information
+        intent_change: bool,
+        /// True if the aircraft is capable of IFR operations (ADS-B class A1 or above)
+        ifr_capability: bool,
+        /// Navigation accuracy category for velocity (NACv), or navigation uncertainty category for
+        /// velocity (NUCr) in earlier versions
+        navigation_accuracy: u8,
         /// Vertical rate in feet per minute, positive values indicate an aircraft is climbing and
-        /// negative values indicate it is descending
-        vertical_rate: i16,
+        /// negative values indicate it is descending, if available
+        vertical_rate: Option<i16>,
+        /// True if the vertical rate exceeds the maximum value that can be encoded (32608 feet per
+        /// minute), so the actual vertical rate is higher
+        vertical_rate_saturated: bool,
         /// Source for vertical rate information
         vertical_rate_source: VerticalRateSource,
+        /// Difference between the GNSS height and the barometric altitude in feet, if available
+        gnss_baro_difference: Option<i16>,
+    },
+    /// Aircraft status message with emergency or priority status (TC 28, subtype 1)
+    AircraftStatus {
+        /// Emergency or priority status
+        emergency_state: EmergencyState,
+        /// Mode A code (squawk)
+        squawk: Squawk,
+    },
+    /// Aircraft status message broadcasting an ACAS resolution advisory (TC 28, subtype 2)
+    ACASResolutionAdvisory {
+        /// Resolution advisory, with the same contents as BDS 3,0
+        resolution_advisory: ResolutionAdvisory,
     },
 }
 